@@ -322,3 +322,204 @@ pub struct SwapBaseOut<'info> {
     #[account(address = spl_token::ID)]
     pub spl_token_program: AccountInfo<'info>,
 }
+
+/// Accounts for a stable AMM `swap_base_in` instruction.
+#[derive(Accounts, Clone)]
+pub struct StableSwapBaseIn<'info> {
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: AccountInfo<'info>,
+    /// CHECK: Safe. Amm authority Account
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_coin Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_coin_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_pc Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_pc_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. Stable curve model data Account
+    pub model_data_account: AccountInfo<'info>,
+    /// CHECK: Safe. serum dex program id
+    pub serum_program: AccountInfo<'info>,
+    /// CHECK: Safe. serum market Account. serum_dex program is the owner.
+    #[account(mut)]
+    pub serum_market: AccountInfo<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub serum_bids: AccountInfo<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub serum_asks: AccountInfo<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub serum_event_queue: AccountInfo<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub serum_coin_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub serum_pc_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. vault_signer Account
+    pub serum_vault_signer: AccountInfo<'info>,
+    /// CHECK: Safe. user source token Account. user Account to swap from.
+    #[account(mut)]
+    pub user_source_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. user destination token Account. user Account to swap to.
+    #[account(mut)]
+    pub user_destination_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. user owner Account
+    #[account(signer)]
+    pub user_source_owner: AccountInfo<'info>,
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
+
+/// Accounts for a stable AMM `swap_base_out` instruction.
+#[derive(Accounts, Clone)]
+pub struct StableSwapBaseOut<'info> {
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: AccountInfo<'info>,
+    /// CHECK: Safe. Amm authority Account
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_coin Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_coin_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_pc Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_pc_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. Stable curve model data Account
+    pub model_data_account: AccountInfo<'info>,
+    /// CHECK: Safe. serum dex program id
+    pub serum_program: AccountInfo<'info>,
+    /// CHECK: Safe. serum market Account. serum_dex program is the owner.
+    #[account(mut)]
+    pub serum_market: AccountInfo<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub serum_bids: AccountInfo<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub serum_asks: AccountInfo<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub serum_event_queue: AccountInfo<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub serum_coin_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub serum_pc_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. vault_signer Account
+    pub serum_vault_signer: AccountInfo<'info>,
+    /// CHECK: Safe. user source token Account. user Account to swap from.
+    #[account(mut)]
+    pub user_source_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. user destination token Account. user Account to swap to.
+    #[account(mut)]
+    pub user_destination_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. user owner Account
+    #[account(signer)]
+    pub user_source_owner: AccountInfo<'info>,
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
+
+/// Accounts for a farm `deposit_v2` instruction.
+#[derive(Accounts, Clone)]
+pub struct FarmDeposit<'info> {
+    /// CHECK: Safe. Farm pool Account
+    #[account(mut)]
+    pub stake_pool: AccountInfo<'info>,
+    /// CHECK: Safe. Farm pool authority, a PDA of the farm program
+    pub pool_authority: AccountInfo<'info>,
+    /// CHECK: Safe. Staker info Account of the depositor, farm program is the owner.
+    #[account(mut)]
+    pub staker_info: AccountInfo<'info>,
+    /// CHECK: Safe. Owner of the staker info and the source lp Account
+    #[account(signer)]
+    pub staker_owner: AccountInfo<'info>,
+    /// CHECK: Safe. Source lp token Account, staker_owner is the owner.
+    #[account(mut)]
+    pub src_lp_token: AccountInfo<'info>,
+    /// CHECK: Safe. Farm lp token vault
+    #[account(mut)]
+    pub vault_lp_token: AccountInfo<'info>,
+    /// CHECK: Safe. Reward token A Account to receive harvested rewards.
+    #[account(mut)]
+    pub dest_reward_token_a: AccountInfo<'info>,
+    /// CHECK: Safe. Farm reward token A vault
+    #[account(mut)]
+    pub vault_reward_token_a: AccountInfo<'info>,
+    /// CHECK: Safe. Reward token B Account to receive harvested rewards.
+    #[account(mut)]
+    pub dest_reward_token_b: AccountInfo<'info>,
+    /// CHECK: Safe. Farm reward token B vault
+    #[account(mut)]
+    pub vault_reward_token_b: AccountInfo<'info>,
+    /// CHECK: Safe. Clock sysvar
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
+
+/// Accounts for a farm `withdraw_v2` instruction.
+#[derive(Accounts, Clone)]
+pub struct FarmWithdraw<'info> {
+    /// CHECK: Safe. Farm pool Account
+    #[account(mut)]
+    pub stake_pool: AccountInfo<'info>,
+    /// CHECK: Safe. Farm pool authority, a PDA of the farm program
+    pub pool_authority: AccountInfo<'info>,
+    /// CHECK: Safe. Staker info Account of the withdrawer, farm program is the owner.
+    #[account(mut)]
+    pub staker_info: AccountInfo<'info>,
+    /// CHECK: Safe. Owner of the staker info
+    #[account(signer)]
+    pub staker_owner: AccountInfo<'info>,
+    /// CHECK: Safe. Destination lp token Account
+    #[account(mut)]
+    pub dest_lp_token: AccountInfo<'info>,
+    /// CHECK: Safe. Farm lp token vault
+    #[account(mut)]
+    pub vault_lp_token: AccountInfo<'info>,
+    /// CHECK: Safe. Reward token A Account to receive harvested rewards.
+    #[account(mut)]
+    pub dest_reward_token_a: AccountInfo<'info>,
+    /// CHECK: Safe. Farm reward token A vault
+    #[account(mut)]
+    pub vault_reward_token_a: AccountInfo<'info>,
+    /// CHECK: Safe. Reward token B Account to receive harvested rewards.
+    #[account(mut)]
+    pub dest_reward_token_b: AccountInfo<'info>,
+    /// CHECK: Safe. Farm reward token B vault
+    #[account(mut)]
+    pub vault_reward_token_b: AccountInfo<'info>,
+    /// CHECK: Safe. Clock sysvar
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
+
+/// Accounts for a farm `update_pool` instruction.
+#[derive(Accounts, Clone)]
+pub struct FarmUpdatePool<'info> {
+    /// CHECK: Safe. Farm pool Account
+    #[account(mut)]
+    pub stake_pool: AccountInfo<'info>,
+    /// CHECK: Safe. Farm lp token vault
+    #[account(mut)]
+    pub vault_lp_token: AccountInfo<'info>,
+    /// CHECK: Safe. Clock sysvar
+    pub clock: Sysvar<'info, Clock>,
+}
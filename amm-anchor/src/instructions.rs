@@ -2,7 +2,7 @@
 
 use crate::*;
 use anchor_lang::{prelude::*, solana_program};
-use raydium_contract_instructions::amm_instruction;
+use raydium_contract_instructions::{amm_instruction, farm_instruction, stable_instruction};
 
 /// Creates and invokes a [raydium_contract_instructions::amm_instruction::pre_initialize] instruction.
 ///
@@ -253,3 +253,171 @@ pub fn swap_base_out<'a, 'b, 'c, 'info>(
     )?;
     Ok(())
 }
+
+/// Creates and invokes a [raydium_contract_instructions::stable_instruction::swap_base_in] instruction.
+///
+/// # Arguments
+///
+/// See [raydium_contract_instructions::stable_instruction::SwapInstructionBaseIn].
+///
+/// * `amount_in` - SOURCE amount to transfer, output to DESTINATION is based on the stable curve.
+/// * `minimum_amount_out` - Minimum amount of DESTINATION token to output, prevents excessive slippage.
+pub fn stable_swap_base_in<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, StableSwapBaseIn<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    let ix = stable_instruction::swap_base_in(
+        ctx.program.key,
+        ctx.accounts.amm.key,
+        ctx.accounts.amm_authority.key,
+        ctx.accounts.amm_open_orders.key,
+        ctx.accounts.pool_coin_token_account.key,
+        ctx.accounts.pool_pc_token_account.key,
+        ctx.accounts.model_data_account.key,
+        ctx.accounts.serum_program.key,
+        ctx.accounts.serum_market.key,
+        ctx.accounts.serum_bids.key,
+        ctx.accounts.serum_asks.key,
+        ctx.accounts.serum_event_queue.key,
+        ctx.accounts.serum_coin_vault_account.key,
+        ctx.accounts.serum_pc_vault_account.key,
+        ctx.accounts.serum_vault_signer.key,
+        ctx.accounts.user_source_token_account.key,
+        ctx.accounts.user_destination_token_account.key,
+        ctx.accounts.user_source_owner.key,
+        amount_in,
+        minimum_amount_out,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates and invokes a [raydium_contract_instructions::stable_instruction::swap_base_out] instruction.
+///
+/// # Arguments
+///
+/// See [raydium_contract_instructions::stable_instruction::SwapInstructionBaseOut].
+///
+/// * `max_amount_in` - Maximum SOURCE amount to transfer, prevents excessive slippage.
+/// * `amount_out` - Exact amount of DESTINATION token to output.
+pub fn stable_swap_base_out<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, StableSwapBaseOut<'info>>,
+    max_amount_in: u64,
+    amount_out: u64,
+) -> Result<()> {
+    let ix = stable_instruction::swap_base_out(
+        ctx.program.key,
+        ctx.accounts.amm.key,
+        ctx.accounts.amm_authority.key,
+        ctx.accounts.amm_open_orders.key,
+        ctx.accounts.pool_coin_token_account.key,
+        ctx.accounts.pool_pc_token_account.key,
+        ctx.accounts.model_data_account.key,
+        ctx.accounts.serum_program.key,
+        ctx.accounts.serum_market.key,
+        ctx.accounts.serum_bids.key,
+        ctx.accounts.serum_asks.key,
+        ctx.accounts.serum_event_queue.key,
+        ctx.accounts.serum_coin_vault_account.key,
+        ctx.accounts.serum_pc_vault_account.key,
+        ctx.accounts.serum_vault_signer.key,
+        ctx.accounts.user_source_token_account.key,
+        ctx.accounts.user_destination_token_account.key,
+        ctx.accounts.user_source_owner.key,
+        max_amount_in,
+        amount_out,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates and invokes a [raydium_contract_instructions::farm_instruction::deposit_v2] instruction.
+///
+/// # Arguments
+///
+/// * `amount` - LP token amount to deposit into the farm. An `amount` of 0 only harvests.
+pub fn farm_deposit_v2<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, FarmDeposit<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let ix = farm_instruction::deposit_v2(
+        ctx.program.key,
+        ctx.accounts.stake_pool.key,
+        ctx.accounts.pool_authority.key,
+        ctx.accounts.staker_info.key,
+        ctx.accounts.staker_owner.key,
+        ctx.accounts.src_lp_token.key,
+        ctx.accounts.vault_lp_token.key,
+        ctx.accounts.dest_reward_token_a.key,
+        ctx.accounts.vault_reward_token_a.key,
+        ctx.accounts.dest_reward_token_b.key,
+        ctx.accounts.vault_reward_token_b.key,
+        ctx.accounts.spl_token_program.key,
+        None,
+        amount,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates and invokes a [raydium_contract_instructions::farm_instruction::withdraw_v2] instruction.
+///
+/// # Arguments
+///
+/// * `amount` - LP token amount to withdraw from the farm, pending rewards are harvested.
+pub fn farm_withdraw_v2<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, FarmWithdraw<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let ix = farm_instruction::withdraw_v2(
+        ctx.program.key,
+        ctx.accounts.stake_pool.key,
+        ctx.accounts.pool_authority.key,
+        ctx.accounts.staker_info.key,
+        ctx.accounts.staker_owner.key,
+        ctx.accounts.dest_lp_token.key,
+        ctx.accounts.vault_lp_token.key,
+        ctx.accounts.dest_reward_token_a.key,
+        ctx.accounts.vault_reward_token_a.key,
+        Some(ctx.accounts.dest_reward_token_b.key),
+        Some(ctx.accounts.vault_reward_token_b.key),
+        ctx.accounts.spl_token_program.key,
+        amount,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates and invokes a [raydium_contract_instructions::farm_instruction::update_pool] instruction.
+pub fn farm_update_pool<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, FarmUpdatePool<'info>>,
+) -> Result<()> {
+    let ix = farm_instruction::update_pool(
+        ctx.program.key,
+        ctx.accounts.stake_pool.key,
+        ctx.accounts.vault_lp_token.key,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
@@ -23,3 +23,13 @@ impl anchor_lang::Id for Amm {
         ID
     }
 }
+
+/// The stable AMM program
+#[derive(Clone)]
+pub struct StableAmm;
+
+impl anchor_lang::Id for StableAmm {
+    fn id() -> Pubkey {
+        raydium_contract_instructions::stable_instruction::ID
+    }
+}
@@ -19,7 +19,7 @@ pub enum BrewStakingError {
     #[msg("Insufficient reward tokens")]
     InsufficientReward,
 
-    #[msg("All reward tokens have not been deposited")]
+    #[msg("Reward vault holds less than the pool pays out over its duration")]
     RewardNotDeposited,
 
     #[msg("Amount should be greator than 0")]
@@ -34,6 +34,126 @@ pub enum BrewStakingError {
     #[msg("Insufficient performance fee for user")]
     InsufficientPerformanceFee,
 
+    #[msg("Pool compounds through a different swap program")]
+    WrongCompoundRoute,
+
+    #[msg("Swap returned less than the minimum amount out")]
+    SlippageExceeded,
+
+    #[msg("Farm passthrough is not enabled for this pool")]
+    FarmNotEnabled,
+
+    #[msg("Pool still has LP tokens deposited in the farm")]
+    FarmStillStaked,
+
+    #[msg("Pool vault is short of the exit and the farm accounts are missing")]
+    FarmAccountsMissing,
+
+    #[msg("Start must be in the future and within the scheduling horizon")]
+    InvalidStartTime,
+
+    #[msg("Signer is not the pool owner")]
+    NotPoolOwner,
+
+    #[msg("Staking on behalf of another wallet is disabled for this pool")]
+    StakeForDisabled,
+
+    #[msg("Signer is neither the position owner nor its claim delegate")]
+    NotPositionOwner,
+
+    #[msg("Reward token account does not belong to the reward recipient")]
+    WrongRewardRecipient,
+
+    #[msg("Pool does not issue position NFTs")]
+    PositionNftDisabled,
+
+    #[msg("Pool issues position NFTs, stake through stake_position")]
+    PositionNftRequired,
+
+    #[msg("Signer does not hold the position NFT")]
+    NotPositionHolder,
+
+    #[msg("Pool still has stake")]
+    PoolNotEmpty,
+
+    #[msg("Vesting and position NFTs can't be combined")]
+    VestingWithPositionNft,
+
+    #[msg("Rewards vest in this pool and can't be swapped or compounded")]
+    RewardsVesting,
+
+    #[msg("Escrow does not belong to the user or the pool's reward mint")]
+    WrongEscrow,
+
+    #[msg("Escrow lock must end in the future and within the maximum lock")]
+    InvalidLockEnd,
+
+    #[msg("Escrow lock has not ended yet")]
+    EscrowLocked,
+
+    #[msg("Escrow lock has ended, withdraw it first")]
+    EscrowExpired,
+
+    #[msg("Successor pool must take the same stake token")]
+    SuccessorMismatch,
+
+    #[msg("Pool has no such successor pool")]
+    NoSuccessorPool,
+
+    #[msg("Pool has not ended yet")]
+    PoolNotEnded,
+
+    #[msg("Account layout is not one that can be migrated")]
+    InvalidAccountVersion,
+
+    #[msg("Only the surplus of a pool vault can be swept")]
+    SweepExceedsSurplus,
+
+    #[msg("Token account can't be swept")]
+    SweepNotAllowed,
+
+    #[msg("Pool vault still holds tokens and can't be closed")]
+    VaultNotEmpty,
+
+    #[msg("Token account can only be left out for native SOL")]
+    NativeMintRequired,
+
+    #[msg("Native SOL transfers need the wSOL account and the native mint")]
+    NativeSolAccountsMissing,
+
+    #[msg("Pool is not listed on this registry page")]
+    NotInRegistry,
+
+    #[msg("Pool still has stakers or rewards to hand out")]
+    PoolStillActive,
+
+    #[msg("Discount or fee rate above 100%")]
+    InvalidFeeRate,
+
+    #[msg("Deploy fee can't be paid in this token")]
+    WrongFeeToken,
+
+    #[msg("Pool duration must be at least one day")]
+    InvalidDuration,
+
+    #[msg("Initial funding is too low to pay out a reward every slot or second")]
+    FundingTooLow,
+
+    #[msg("Vested rewards are still waiting to be withdrawn")]
+    VestingOutstanding,
+
+    #[msg("Early-exit penalty is above the maximum or has no decay period")]
+    InvalidPenalty,
+
+    #[msg("Penalties can only be redistributed in pools staking their reward token")]
+    PenaltyRedistributionUnsupported,
+
+    #[msg("Boosted pools need the escrow and escrow totals accounts")]
+    EscrowAccountsMissing,
+
+    #[msg("Position NFT rewards always go to the holder")]
+    PositionClaimSettings,
+
     ////////////
     #[msg("Wrong reserve owner. Must be a system account")]
     WrongReserveOwner, // 6000 0x1770
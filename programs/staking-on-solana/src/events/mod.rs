@@ -1,33 +1,433 @@
 use anchor_lang::prelude::*;
 
+/// Layout version stamped on every event, bumped whenever an event's fields change
+pub const EVENT_VERSION: u8 = 4;
+
+// Every pool event carries the pool config key, a per-pool sequence number
+// (PoolState.event_seq) and the slot it was emitted in, so indexers can
+// order events and detect gaps without replaying transactions.
+//
+// Schedule points (*_point) are slots, or unix timestamps when the event's
+// time_based flag is set, while `slot` is always the emitting slot.
+
+#[event]
+pub struct PlatformInitialized {
+    pub version: u8,
+    pub platform: Pubkey,
+    pub treasury: Pubkey,
+    pub deploy_fee: u64,
+    pub performance_fee: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PlatformMigrated {
+    pub version: u8,
+    pub platform: Pubkey,
+    pub treasury: Pubkey,
+    pub account_version: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FeeScheduleSet {
+    pub version: u8,
+    pub platform: Pubkey,
+    pub fee_mint: Pubkey,
+    pub deploy_fee_token: u64,
+    pub cancel_refund_bps: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CreatorTierSet {
+    pub version: u8,
+    pub platform: Pubkey,
+    pub creator: Pubkey,
+    pub deploy_fee_discount_bps: u16,
+    pub performance_fee_discount_bps: u16,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PoolCreated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub creator: Pubkey,
+    pub pool_state: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub initial_funding: u64,
+    pub reward_rate: u64,
+    pub duration: u16,
+    pub stake_fee: u16,
+    pub unstake_fee: u16,
+    pub time_based: bool,
+    pub deploy_fee: u64,
+    /// Token the deploy fee was paid in, the default key for lamports
+    pub deploy_fee_mint: Pubkey,
+}
+
 #[event]
 pub struct NewStartAndEndSlots {
-    pub start_slot: u64,
-    pub end_slot: u64,
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub time_based: bool,
+    pub start_point: u64,
+    pub end_point: u64,
+    pub reward_amount: u64,
+}
+
+#[event]
+pub struct SeasonStarted {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub season: u32,
+    pub time_based: bool,
+    pub start_point: u64,
+    pub end_point: u64,
+    pub reward_rate: u64,
+    pub duration: u16,
+    pub funding: u64,
+    pub reward_amount: u64,
 }
 
 #[event]
 pub struct RewardsStop {
-    pub end_slot: u64,
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub time_based: bool,
+    pub end_point: u64,
+    pub total_staked: u64,
+    pub reward_amount: u64,
 }
 
 #[event]
 pub struct Deposit {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
     pub staker: Pubkey,
+    /// Amount credited to the position, after the stake fee
     pub amount: u64,
+    pub stake_fee: u64,
+    pub performance_fee: u64,
+    pub user_staked: u64,
+    pub total_staked: u64,
 }
 
+#[event]
+pub struct Withdraw {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub staker: Pubkey,
+    /// Amount removed from the position, before the unstake fee
+    pub amount: u64,
+    pub unstake_fee: u64,
+    pub performance_fee: u64,
+    pub user_staked: u64,
+    pub total_staked: u64,
+}
+
+/// `performance_fee` is zero when the claim rides along a stake or unstake,
+/// the fee is then reported on the Deposit or Withdraw event.
 #[event]
 pub struct RewardClaim {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
     pub claimer: Pubkey,
     pub amount: u64,
+    pub performance_fee: u64,
+    pub reward_amount: u64,
+    pub paid_rewards: u64,
 }
 
 #[event]
 pub struct Compound {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
     pub compounder: Pubkey,
+    /// Reward tokens taken from the pool
+    pub amount: u64,
+    /// Stake tokens added to the position
+    pub staked_amount: u64,
+    pub performance_fee: u64,
+    pub user_staked: u64,
+    pub total_staked: u64,
+    pub reward_amount: u64,
+}
+
+#[event]
+pub struct StakeForSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct ClaimSettingsSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub user: Pubkey,
+    pub reward_recipient: Pubkey,
+    pub claim_delegate: Pubkey,
+}
+
+#[event]
+pub struct PositionNftModeSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct VestingSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub vesting_period: u64,
+    pub redistribute_forfeits: bool,
+}
+
+#[event]
+pub struct VestedWithdraw {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub user_locked: u64,
+    pub total_vesting: u64,
+}
+
+#[event]
+pub struct RewardsForfeited {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub redistributed: bool,
+    pub total_vesting: u64,
+}
+
+#[event]
+pub struct EarlyExitPenaltySet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub penalty_bps: u16,
+    pub decay_days: u16,
+    pub redistribute: bool,
+}
+
+#[event]
+pub struct PenaltyRedistributed {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub acc_token_per_share: u64,
+}
+
+#[event]
+pub struct BoostSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub enabled: bool,
+    pub escrow_totals: Pubkey,
+}
+
+#[event]
+pub struct SuccessorPoolSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub successor_pool: Pubkey,
+}
+
+#[event]
+pub struct PositionMigrated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub user: Pubkey,
+    pub successor_pool: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PoolMigrated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub config_migrated: bool,
+    pub state_migrated: bool,
+    pub account_version: u8,
+}
+
+#[event]
+pub struct UserInfoMigrated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub user: Pubkey,
+    pub staked_amount: u64,
+    pub account_version: u8,
+}
+
+#[event]
+pub struct PoolCancelled {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub refunded_rewards: u64,
+    pub refunded_deploy_fee: u64,
+}
+
+#[event]
+pub struct PoolClosed {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub refunded_rewards: u64,
+}
+
+#[event]
+pub struct PoolMarkedClosed {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub registry_index: u64,
+}
+
+#[event]
+pub struct TokensSwept {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub source: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
     pub amount: u64,
 }
+
+// Escrows are not tied to a pool, so their events carry no pool sequence number.
+
+#[event]
+pub struct EscrowTotalsCreated {
+    pub version: u8,
+    pub slot: u64,
+    pub escrow_totals: Pubkey,
+    pub platform: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+}
+
+#[event]
+pub struct EscrowLock {
+    pub version: u8,
+    pub slot: u64,
+    pub escrow: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub lock_end: u64,
+    pub total_locked: u64,
+}
+
+#[event]
+pub struct EscrowWithdraw {
+    pub version: u8,
+    pub slot: u64,
+    pub escrow: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_locked: u64,
+}
+
+#[event]
+pub struct FarmSet {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub farm_program: Pubkey,
+    pub farm_pool: Pubkey,
+}
+
+#[event]
+pub struct FarmDeposited {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub amount: u64,
+    pub harvested_a: u64,
+    pub harvested_b: u64,
+    pub farm_staked: u64,
+}
+
+#[event]
+pub struct FarmWithdrawn {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub amount: u64,
+    pub harvested_a: u64,
+    pub harvested_b: u64,
+    pub farm_staked: u64,
+}
+
+#[event]
+pub struct FarmRewardClaim {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub seq: u64,
+    pub slot: u64,
+    pub claimer: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
 // #[event]
 // pub struct ChangeAuthorityEvent {
 //     pub state: Pubkey,
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, spl_token, Mint, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Undo a pool that never started: the funding goes back to the creator and the
+// pool accounts are closed. The platform refunds its share of the deploy fee
+// when the treasury co-signs.
+pub fn handler(ctx: Context<CancelPool>) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let pool_key = pool_config.key();
+
+    require!(start_point(pool_config) == 0, BrewStakingError::PoolAlreadyStarted);
+    require!(pool_state.total_staked == 0, BrewStakingError::PoolNotEmpty);
+
+    // Refund the whole reward vault, a pool that never started owes nothing out of it
+    let refunded_rewards = ctx.accounts.pool_reward_token_vault.amount;
+    if refunded_rewards > 0 {
+        if let Some(creator_reward_token_vault) = &ctx.accounts.creator_reward_token_vault {
+            transfer_tokens(
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                creator_reward_token_vault.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                refunded_rewards,
+                ctx.accounts.token_program.to_account_info()
+            )?;
+        } else {
+            unwrap_sol(
+                pool_config.reward_mint,
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.wsol_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.native_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                refunded_rewards,
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info()
+            )?;
+        }
+        pool_state.reward_amount = 0;
+    }
+
+    ctx.accounts.pool_reward_token_vault.reload()?;
+    require!(
+        close_empty_vault(
+            &ctx.accounts.pool_reward_token_vault,
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        )?,
+        BrewStakingError::VaultNotEmpty
+    );
+    // Nothing is staked, stray stake tokens have to be swept before cancelling
+    if pool_config.pool_stake_token_vault != pool_config.pool_reward_token_vault {
+        require!(
+            close_empty_vault(
+                &ctx.accounts.pool_stake_token_vault,
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.token_program.to_account_info()
+            )?,
+            BrewStakingError::VaultNotEmpty
+        );
+    }
+
+    let mut refunded_deploy_fee = 0;
+    if let Some(treasury) = &ctx.accounts.treasury {
+        let fee_schedule = &ctx.accounts.fee_schedule;
+        if !fee_schedule.data_is_empty() {
+            let fee_schedule = FeeSchedule::try_deserialize(
+                &mut &fee_schedule.try_borrow_data()?[..]
+            )?;
+            refunded_deploy_fee =
+                (pool_config.deploy_fee_paid * (fee_schedule.cancel_refund_bps as u64)) /
+                PERCENT_PRECISION;
+        }
+        if refunded_deploy_fee > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: treasury.to_account_info(),
+                to: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+            system_program::transfer(cpi_ctx, refunded_deploy_fee)?;
+        }
+    }
+
+    mark_registry_closed(&mut ctx.accounts.registry_page, pool_config, pool_key)?;
+
+    emit!(PoolCancelled {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        refunded_rewards,
+        refunded_deploy_fee,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelPool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: vault authority, co-signs every transfer out of a vault
+    pub admin: Signer<'info>,
+
+    /// Co-signs to refund part of the deploy fee
+    #[account(mut, constraint = treasury.key() == platform.treasury)]
+    pub treasury: Option<Signer<'info>>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = pool_stake_token_vault,
+        has_one = pool_reward_token_vault,
+        close = owner
+    )]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr, close = owner)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            REGISTRY_SEED,
+            platform.key().as_ref(),
+            &registry_page_of(pool_config_account.registry_index).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+
+    /// CHECK: FeeSchedule of the platform, may not exist
+    #[account(seeds = [FEE_SCHEDULE_SEED, platform.key().as_ref()], bump)]
+    pub fee_schedule: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Left out for native SOL rewards, which are refunded in lamports
+    #[account(
+        mut,
+        token::mint = pool_config_account.reward_mint,
+        token::authority = owner
+    )]
+    pub creator_reward_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Temporary wSOL account at [WSOL_SEED, owner], only for native SOL pools
+    /// CHECK: created and closed again by unwrap_sol
+    #[account(mut)]
+    pub wsol_account: Option<UncheckedAccount<'info>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub native_mint: Option<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<ClaimFarmReward>) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+
+    let (owed_a, owed_b) = pay_farm_rewards(
+        pool_state,
+        user_info,
+        ctx.accounts.pool_farm_reward_a_vault.to_account_info(),
+        ctx.accounts.pool_farm_reward_b_vault.to_account_info(),
+        ctx.accounts.user_farm_reward_a_vault.to_account_info(),
+        ctx.accounts.user_farm_reward_b_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    emit!(FarmRewardClaim {
+        version: EVENT_VERSION,
+        pool: ctx.accounts.pool_config_account.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        claimer: ctx.accounts.claimer.key(),
+        amount_a: owed_a,
+        amount_b: owed_b,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimFarmReward<'info> {
+    pub claimer: Signer<'info>,
+
+    /// CHECK:
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), claimer.key().as_ref()],
+        bump
+    )]
+    pub user_info: Account<'info, UserInfo>,
+
+    #[account(has_one = pool_farm_reward_a_vault, has_one = pool_farm_reward_b_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut)]
+    pub pool_farm_reward_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_farm_reward_b_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_farm_reward_a_vault.owner == reward_payout_owner(&user_info, claimer.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_farm_reward_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_farm_reward_b_vault.owner == reward_payout_owner(&user_info, claimer.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_farm_reward_b_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, token::Token>,
+}
@@ -1,21 +1,22 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{ self, TokenAccount, Transfer };
+use anchor_spl::token::{ self, spl_token, Mint, TokenAccount, Transfer };
 
 use crate::state::*;
 use crate::utils::*;
 use crate::error::*;
-use crate::events::*;
 
 pub fn handler(ctx: Context<ClaimReward>) -> Result<()> {
     let pool_config = &ctx.accounts.pool_config_account;
     let pool_state = &mut ctx.accounts.pool_state_account;
     let user_info = &mut ctx.accounts.user_info;
     let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
 
     // Transfer Performance Fee from user to treasury
     let user_balance = ctx.accounts.claimer.to_account_info().lamports();
-    require!(user_balance > platform.performance_fee, BrewStakingError::InsufficientDeployFee);
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
 
     let cpi_program = ctx.accounts.system_program.to_account_info();
     let cpi_accounts = system_program::Transfer {
@@ -23,7 +24,7 @@ pub fn handler(ctx: Context<ClaimReward>) -> Result<()> {
         to: ctx.accounts.treasury.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    system_program::transfer(cpi_ctx, platform.performance_fee)?;
+    system_program::transfer(cpi_ctx, performance_fee)?;
 
     let _ = update_pool(pool_config, pool_state);
 
@@ -31,45 +32,54 @@ pub fn handler(ctx: Context<ClaimReward>) -> Result<()> {
         return Ok(());
     }
 
-    let precision_factor = get_precision_factor(pool_config);
-
     // Transfer the user his reward so far
-    let pending =
-        (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor -
-        user_info.reward_debt;
-
+    let pending = take_pending_reward(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.owner.key(),
+        performance_fee,
+        slot
+    )?;
     if pending > 0 {
-        require!(
-            available_reward_tokens(pool_config, pool_state) >= pending,
-            BrewStakingError::InsufficientReward
-        );
-
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.pool_reward_token_vault.to_account_info(),
-            to: ctx.accounts.user_reward_token_vault.to_account_info(),
-            authority: ctx.accounts.admin.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, pending)?;
-
-        pool_state.reward_amount -= pending;
-
-        pool_state.total_earned = if pool_state.total_earned > pending {
-            pool_state.total_earned - pending
+        if let Some(user_reward_token_vault) = &ctx.accounts.user_reward_token_vault {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.pool_reward_token_vault.to_account_info(),
+                to: user_reward_token_vault.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token::transfer(cpi_ctx, pending)?;
         } else {
-            0
-        };
-        pool_state.paid_rewards += pending;
-
-        emit!(RewardClaim {
-            claimer: ctx.accounts.claimer.key(),
-            amount: pending,
-        });
+            let Some(payout_wallet) = &ctx.accounts.payout_wallet else {
+                return err!(BrewStakingError::NativeSolAccountsMissing);
+            };
+            unwrap_sol(
+                pool_config.reward_mint,
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.wsol_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.native_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.claimer.to_account_info(),
+                payout_wallet.to_account_info(),
+                pending,
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info()
+            )?;
+        }
     }
 
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.owner.key()
+    )?;
+    update_working_amount(pool_config, pool_state, user_info, ve_balance, ve_total);
     user_info.reward_debt =
-        (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor;
+        (user_info.working_amount * pool_state.acc_token_per_share) /
+        get_precision_factor(pool_config);
 
     Ok(())
 }
@@ -88,7 +98,15 @@ pub struct ClaimReward<'info> {
     #[account(mut)]
     pub treasury: AccountInfo<'info>,
 
-    #[account(mut)]
+    /// CHECK: Safe. Owner of the position, only used to derive it
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = can_claim_for(&user_info, owner.key(), claimer.key()) @ BrewStakingError::NotPositionOwner
+    )]
     pub user_info: Account<'info, UserInfo>,
 
     #[account(has_one = pool_reward_token_vault)]
@@ -99,12 +117,37 @@ pub struct ClaimReward<'info> {
 
     pub platform: Account<'info, PlatformInfo>,
 
-    #[account(mut)]
-    pub user_reward_token_vault: Account<'info, TokenAccount>,
+    /// Left out for native SOL rewards, which are paid to `payout_wallet` in lamports
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, owner.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Safe. Wallet native SOL rewards are paid to
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == reward_payout_owner(&user_info, owner.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
 
     #[account(mut)]
     pub pool_reward_token_vault: Account<'info, TokenAccount>,
 
+    /// Vote-escrow lock boosting the owner in boosted pools
+    /// CHECK: Escrow PDA of the owner on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    /// Temporary wSOL account at [WSOL_SEED, claimer], only for native SOL pools
+    /// CHECK: created and closed again by unwrap_sol
+    #[account(mut)]
+    pub wsol_account: Option<UncheckedAccount<'info>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub native_mint: Option<Account<'info, Mint>>,
+
     pub token_program: Program<'info, token::Token>,
 
     pub system_program: Program<'info, System>,
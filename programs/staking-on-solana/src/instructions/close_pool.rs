@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, spl_token, Mint, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Wind down an ended pool once every staker has left. Rewards nobody earned go
+// back to the owner, then the pool accounts are closed to the owner.
+pub fn handler(ctx: Context<ClosePool>) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let pool_key = pool_config.key();
+
+    require!(start_point(pool_config) > 0, BrewStakingError::PoolNotStarted);
+    require!(
+        current_point(pool_config)? >= end_point(pool_config),
+        BrewStakingError::PoolNotEnded
+    );
+    require!(
+        pool_state.total_staked == 0 && pool_state.farm_staked == 0,
+        BrewStakingError::PoolNotEmpty
+    );
+    require!(pool_state.total_vesting == 0, BrewStakingError::VestingOutstanding);
+
+    // With no stakers left, nothing of the remaining budget is owed anymore
+    let refunded_rewards = pool_state.reward_amount.min(ctx.accounts.pool_reward_token_vault.amount);
+    if refunded_rewards > 0 {
+        if let Some(creator_reward_token_vault) = &ctx.accounts.creator_reward_token_vault {
+            transfer_tokens(
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                creator_reward_token_vault.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                refunded_rewards,
+                ctx.accounts.token_program.to_account_info()
+            )?;
+        } else {
+            unwrap_sol(
+                pool_config.reward_mint,
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.wsol_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.native_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.owner.to_account_info(),
+                refunded_rewards,
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info()
+            )?;
+        }
+        pool_state.reward_amount -= refunded_rewards;
+    }
+
+    ctx.accounts.pool_reward_token_vault.reload()?;
+    close_empty_vault(
+        &ctx.accounts.pool_reward_token_vault,
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+    if pool_config.pool_stake_token_vault != pool_config.pool_reward_token_vault {
+        close_empty_vault(
+            &ctx.accounts.pool_stake_token_vault,
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
+
+    mark_registry_closed(&mut ctx.accounts.registry_page, pool_config, pool_key)?;
+
+    emit!(PoolClosed {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        refunded_rewards,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: vault authority, co-signs every transfer out of a vault
+    pub admin: Signer<'info>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = pool_stake_token_vault,
+        has_one = pool_reward_token_vault,
+        close = owner
+    )]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr, close = owner)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(
+        mut,
+        seeds = [
+            REGISTRY_SEED,
+            platform.key().as_ref(),
+            &registry_page_of(pool_config_account.registry_index).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Left out for native SOL rewards, which are refunded in lamports
+    #[account(
+        mut,
+        token::mint = pool_config_account.reward_mint,
+        token::authority = owner
+    )]
+    pub creator_reward_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Temporary wSOL account at [WSOL_SEED, owner], only for native SOL pools
+    /// CHECK: created and closed again by unwrap_sol
+    #[account(mut)]
+    pub wsol_account: Option<UncheckedAccount<'info>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub native_mint: Option<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    pub system_program: Program<'info, System>,
+}
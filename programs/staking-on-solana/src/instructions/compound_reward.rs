@@ -2,22 +2,31 @@ use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_spl::token::{ self, TokenAccount };
 // use raydium_contract_instructions::amm_instruction;
-use amm_anchor::SwapBaseIn;
+use amm_anchor::{ StableSwapBaseIn, SwapBaseIn };
 
 use crate::state::*;
 use crate::utils::*;
 use crate::error::*;
 use crate::events::*;
 
-pub fn handler(ctx: Context<CompoundReward>) -> Result<()> {
+// `minimum_amount_out` bounds the swap of the reward into the stake token, it's
+// ignored by pools staking their reward token.
+pub fn handler(ctx: Context<CompoundReward>, minimum_amount_out: u64) -> Result<()> {
     let pool_config = &mut ctx.accounts.pool_config_account;
     let pool_state = &mut ctx.accounts.pool_state_account;
     let user_info = &mut ctx.accounts.user_info;
     let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let pool_key = pool_config.key();
+    let slot = Clock::get()?.slot;
+
+    // Compounded rewards would be stake the holder can withdraw at once, skipping the
+    // vesting schedule
+    require!(pool_config.vesting_period == 0, BrewStakingError::RewardsVesting);
 
     // Transfer Performance Fee from user to treasury
     let user_balance = ctx.accounts.user.to_account_info().lamports();
-    require!(user_balance > platform.performance_fee, BrewStakingError::InsufficientDeployFee);
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
 
     let cpi_program = ctx.accounts.system_program.to_account_info();
     let cpi_accounts = system_program::Transfer {
@@ -25,7 +34,7 @@ pub fn handler(ctx: Context<CompoundReward>) -> Result<()> {
         to: ctx.accounts.treasury.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    system_program::transfer(cpi_ctx, platform.performance_fee)?;
+    system_program::transfer(cpi_ctx, performance_fee)?;
 
     let _ = update_pool(pool_config, pool_state);
 
@@ -34,10 +43,11 @@ pub fn handler(ctx: Context<CompoundReward>) -> Result<()> {
     }
 
     let precision_factor = get_precision_factor(pool_config);
+    settle_farm_rewards(pool_state, user_info);
 
     // Transfer the user his reward so far
     let mut pending =
-        (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor -
+        (user_info.working_amount * pool_state.acc_token_per_share) / precision_factor -
         user_info.reward_debt;
 
     if pending > 0 {
@@ -54,17 +64,12 @@ pub fn handler(ctx: Context<CompoundReward>) -> Result<()> {
 
         pool_state.paid_rewards += pending;
 
-        emit!(Compound {
-            compounder: ctx.accounts.user.key(),
-            amount: pending,
-        });
+        let reward_compounded = pending;
 
-        // swap stake token to reward token
+        // swap reward token to stake token
         if pool_config.stake_mint != pool_config.reward_mint {
-            let pool_reward_balance_before = ctx.accounts.pool_reward_token_vault.amount;
-            msg!("pool_reward_balance_before {}", pool_reward_balance_before);
+            let pool_stake_balance_before = ctx.accounts.pool_stake_token_vault.amount;
 
-            let minimum_amount_out = 1;
             /*
             let ix = amm_instruction::swap_base_in(
                 &amm_instruction::ID,
@@ -119,55 +124,111 @@ pub fn handler(ctx: Context<CompoundReward>) -> Result<()> {
                 // ctx.bumps.
             )?;
 */
-            let swap_base_in_accounts = SwapBaseIn {
-                amm: ctx.accounts.amm.clone(),
-                amm_authority: ctx.accounts.amm_authority.clone(),
-                amm_open_orders: ctx.accounts.amm_open_orders.clone(),
-                amm_target_orders: ctx.accounts.amm_target_orders.clone(),
-                pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
-                pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
-                serum_program: ctx.accounts.serum_program.clone(),
-                serum_market: ctx.accounts.serum_market.clone(),
-                serum_bids: ctx.accounts.serum_bids.clone(),
-                serum_asks: ctx.accounts.serum_asks.clone(),
-                serum_event_queue: ctx.accounts.serum_event_queue.clone(),
-                serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
-                serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
-                serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
-                user_source_token_account: ctx.accounts.pool_reward_token_vault
-                    .to_account_info()
-                    .clone(),
-                user_destination_token_account: ctx.accounts.pool_stake_token_vault
-                    .to_account_info()
-                    .clone(),
-                user_source_owner: ctx.accounts.admin.to_account_info().clone(),
-                spl_token_program: ctx.accounts.spl_token_program.clone(),
-            };
-
-            // Specify the program for the CPI call
-            let swap_base_in_program = ctx.accounts.amm_program.clone();
-
-            // Create a CpiContext with the specified accounts and program
-            let cpi_ctx = CpiContext::new(swap_base_in_program, swap_base_in_accounts);
-            let _ = amm_anchor::swap_base_in(cpi_ctx, pending, minimum_amount_out);
-
-            let pool_reward_balance_after = ctx.accounts.pool_reward_token_vault.amount;
-            msg!("pool_reward_balance_after {}", pool_reward_balance_after);
-
-            pending = pool_reward_balance_after - pool_reward_balance_before;
+            if pool_config.use_stable_swap {
+                // Stable pairs swap along the stable curve instead
+                let (Some(stable_amm_program), Some(model_data_account)) = (
+                    &ctx.accounts.stable_amm_program,
+                    &ctx.accounts.model_data_account,
+                ) else {
+                    return err!(BrewStakingError::WrongCompoundRoute);
+                };
+                let swap_base_in_accounts = StableSwapBaseIn {
+                    amm: ctx.accounts.amm.clone(),
+                    amm_authority: ctx.accounts.amm_authority.clone(),
+                    amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+                    pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+                    pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+                    model_data_account: model_data_account.to_account_info(),
+                    serum_program: ctx.accounts.serum_program.clone(),
+                    serum_market: ctx.accounts.serum_market.clone(),
+                    serum_bids: ctx.accounts.serum_bids.clone(),
+                    serum_asks: ctx.accounts.serum_asks.clone(),
+                    serum_event_queue: ctx.accounts.serum_event_queue.clone(),
+                    serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
+                    serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
+                    serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
+                    user_source_token_account: ctx.accounts.pool_reward_token_vault.to_account_info(),
+                    user_destination_token_account: ctx.accounts.pool_stake_token_vault.to_account_info(),
+                    user_source_owner: ctx.accounts.admin.to_account_info(),
+                    spl_token_program: ctx.accounts.spl_token_program.clone(),
+                };
+                let cpi_ctx = CpiContext::new(
+                    stable_amm_program.to_account_info(),
+                    swap_base_in_accounts
+                );
+                amm_anchor::stable_swap_base_in(cpi_ctx, pending, minimum_amount_out)?;
+            } else {
+                let swap_base_in_accounts = SwapBaseIn {
+                    amm: ctx.accounts.amm.clone(),
+                    amm_authority: ctx.accounts.amm_authority.clone(),
+                    amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+                    amm_target_orders: ctx.accounts.amm_target_orders.clone(),
+                    pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+                    pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+                    serum_program: ctx.accounts.serum_program.clone(),
+                    serum_market: ctx.accounts.serum_market.clone(),
+                    serum_bids: ctx.accounts.serum_bids.clone(),
+                    serum_asks: ctx.accounts.serum_asks.clone(),
+                    serum_event_queue: ctx.accounts.serum_event_queue.clone(),
+                    serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
+                    serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
+                    serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
+                    user_source_token_account: ctx.accounts.pool_reward_token_vault
+                        .to_account_info()
+                        .clone(),
+                    user_destination_token_account: ctx.accounts.pool_stake_token_vault
+                        .to_account_info()
+                        .clone(),
+                    user_source_owner: ctx.accounts.admin.to_account_info().clone(),
+                    spl_token_program: ctx.accounts.spl_token_program.clone(),
+                };
+
+                // Specify the program for the CPI call
+                let swap_base_in_program = ctx.accounts.amm_program.clone();
+
+                // Create a CpiContext with the specified accounts and program
+                let cpi_ctx = CpiContext::new(swap_base_in_program, swap_base_in_accounts);
+                amm_anchor::swap_base_in(cpi_ctx, pending, minimum_amount_out)?;
+            }
+            pool_state.reward_amount -= pending;
+
+            ctx.accounts.pool_stake_token_vault.reload()?;
+            let pool_stake_balance_after = ctx.accounts.pool_stake_token_vault.amount;
+
+            pending = pool_stake_balance_after - pool_stake_balance_before;
+            require!(pending >= minimum_amount_out, BrewStakingError::SlippageExceeded);
         }
 
+        // Compounded rewards are new stake and age like a top-up
+        user_info.deposit_slot = weighted_deposit_slot(pool_config, user_info, pending, slot);
         pool_state.total_staked += pending;
         user_info.staked_amount += pending;
 
-        emit!(Deposit {
-            staker: ctx.accounts.user.key(),
-            amount: pending,
+        emit!(Compound {
+            version: EVENT_VERSION,
+            pool: pool_key,
+            seq: next_event_seq(pool_state),
+            slot,
+            compounder: ctx.accounts.owner.key(),
+            amount: reward_compounded,
+            staked_amount: pending,
+            performance_fee,
+            user_staked: user_info.staked_amount,
+            total_staked: pool_state.total_staked,
+            reward_amount: pool_state.reward_amount,
         });
     }
 
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.owner.key()
+    )?;
+    update_working_amount(pool_config, pool_state, user_info, ve_balance, ve_total);
     user_info.reward_debt =
-        (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor;
+        (user_info.working_amount * pool_state.acc_token_per_share) / precision_factor;
+    sync_farm_reward_debts(pool_state, user_info);
     Ok(())
 }
 
@@ -191,7 +252,15 @@ pub struct CompoundReward<'info> {
     #[account(mut)]
     pub pool_state_account: Box<Account<'info, PoolState>>,
 
-    #[account(mut)]
+    /// CHECK: Safe. Owner of the position, only used to derive it
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = can_claim_for(&user_info, owner.key(), user.key()) @ BrewStakingError::NotPositionOwner
+    )]
     pub user_info: Box<Account<'info, UserInfo>>,
 
     pub platform: Account<'info, PlatformInfo>,
@@ -205,11 +274,21 @@ pub struct CompoundReward<'info> {
     // #[account(mut)]
     // pub treasury_stake_token_vault: Box<Account<'info, TokenAccount>>,
 
+    /// Vote-escrow lock boosting the owner in boosted pools
+    /// CHECK: Escrow PDA of the owner on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
     pub token_program: Program<'info, token::Token>,
 
     // Raydium Swap Accounts
-    /// CHECK: Safe. amm program
+    /// CHECK: Safe. amm program, unused by stable-swap pools
     pub amm_program: AccountInfo<'info>,
+    /// Stable amm program, only for stable-swap pools
+    pub stable_amm_program: Option<Program<'info, amm_anchor::StableAmm>>,
+    /// CHECK: Safe. stable curve model data Account, only for stable-swap pools
+    pub model_data_account: Option<UncheckedAccount<'info>>,
     /// CHECK: Safe. amm Account
     #[account(mut)]
     pub amm: AccountInfo<'info>,
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ Mint, TokenAccount };
+
+use crate::state::*;
+use crate::events::*;
+
+// Only the platform treasury can open escrows on a mint, so nobody else can pick
+// the vault locked tokens go to.
+pub fn handler(ctx: Context<CreateEscrowTotals>) -> Result<()> {
+    let escrow_totals = &mut ctx.accounts.escrow_totals;
+
+    escrow_totals.mint = ctx.accounts.mint.key();
+    escrow_totals.vault = ctx.accounts.vault.key();
+    escrow_totals.total_locked = 0;
+    escrow_totals.locked_end_sum = 0;
+    escrow_totals.active_locked = 0;
+    escrow_totals.checkpoint_time = Clock::get()?.unix_timestamp as u64;
+    escrow_totals.version = CURRENT_ACCOUNT_VERSION;
+    escrow_totals.platform = ctx.accounts.platform.key();
+
+    emit!(EscrowTotalsCreated {
+        version: EVENT_VERSION,
+        slot: Clock::get()?.slot,
+        escrow_totals: escrow_totals.key(),
+        platform: escrow_totals.platform,
+        mint: escrow_totals.mint,
+        vault: escrow_totals.vault,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateEscrowTotals<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub treasury: Signer<'info>,
+
+    #[account(has_one = treasury)]
+    pub platform: Account<'info, PlatformInfo>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ESCROW_TOTALS_SIZE,
+        seeds = [ESCROW_TOTALS_SEED, platform.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_totals: Box<Account<'info, EscrowTotals>>,
+
+    #[account(token::mint = mint, token::authority = admin)]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
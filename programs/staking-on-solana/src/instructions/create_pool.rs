@@ -1,31 +1,53 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{ self, Mint, TokenAccount, Transfer };
+use anchor_spl::token::{ self, spl_token, Mint, TokenAccount, Transfer };
 // use spl_associated_token_account::{ get_associated_token_address, create_associated_token_account };
 
 use crate::state::*;
+use crate::utils::*;
 use crate::error::*;
+use crate::events::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreatePool>,
     stake_fee: u16,
     unstake_fee: u16,
     initial_funding: u64,
-    reward_per_slot: u64,
-    duration: u16
+    reward_rate: u64,
+    duration: u16,
+    use_stable_swap: bool,
+    time_based: bool
 ) -> Result<()> {
     // Validate stake and unstake fees
     require!(stake_fee <= MAX_FEE, BrewStakingError::InvalidStakeFee);
     require!(unstake_fee <= MAX_FEE, BrewStakingError::InvalidUnstakeFee);
 
     let pool_config = &mut ctx.accounts.pool_config_account;
-    let platform = &ctx.accounts.platform;
+
+    // Platforms without a fee schedule charge the flat deploy fee
+    let fee_schedule = if ctx.accounts.fee_schedule.data_is_empty() {
+        None
+    } else {
+        Some(FeeSchedule::try_deserialize(&mut &ctx.accounts.fee_schedule.try_borrow_data()?[..])?)
+    };
+    let creator_tier = ctx.accounts.creator_tier.as_ref().map(|tier| &***tier);
+    let fee_rate = deploy_fee_bps(fee_schedule.as_ref(), creator_tier, duration);
 
     pool_config.owner = ctx.accounts.creator.key();
     pool_config.stake_fee = stake_fee;
     pool_config.unstake_fee = unstake_fee;
     pool_config.duration = duration;
-    pool_config.reward_per_slot = reward_per_slot;
+    pool_config.time_based = time_based;
+
+    // A zero `reward_rate` spreads the initial funding evenly over the duration
+    let reward_rate = if reward_rate == 0 {
+        derive_reward_rate(pool_config, initial_funding)?
+    } else {
+        reward_rate
+    };
+    set_reward_rate(pool_config, reward_rate);
+    pool_config.use_stable_swap = use_stable_swap;
 
     pool_config.stake_mint = ctx.accounts.stake_mint.key();
     pool_config.reward_mint = ctx.accounts.reward_mint.key();
@@ -34,6 +56,10 @@ pub fn handler(
     pool_config.pool_reward_token_vault = ctx.accounts.pool_reward_token_vault.key();
     pool_config.pool_stake_token_vault = ctx.accounts.pool_stake_token_vault.key();
     pool_config.state_addr = ctx.accounts.pool_state_account.key();
+    pool_config.version = CURRENT_ACCOUNT_VERSION;
+    pool_config.performance_fee_discount_bps = creator_tier.map_or(0, |tier| {
+        tier.performance_fee_discount_bps
+    });
 
     // let creator_reward_token_vault = get_associated_token_address(
     //     &ctx.accounts.creator.key(),
@@ -41,31 +67,129 @@ pub fn handler(
     // );
 
     // Transfer reward token from creator to pool account
-    let cpi_accounts = Transfer {
-        // from: creator_reward_token_vault.,
-        from: ctx.accounts.creator_reward_token_vault.to_account_info(),
-        to: ctx.accounts.pool_reward_token_vault.to_account_info(),
-        authority: ctx.accounts.creator.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, initial_funding)?;
+    if let Some(creator_reward_token_vault) = &ctx.accounts.creator_reward_token_vault {
+        let cpi_accounts = Transfer {
+            // from: creator_reward_token_vault.,
+            from: creator_reward_token_vault.to_account_info(),
+            to: ctx.accounts.pool_reward_token_vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, initial_funding)?;
+    } else {
+        // Native SOL rewards are funded in lamports
+        require_keys_eq!(
+            pool_config.reward_mint,
+            spl_token::native_mint::ID,
+            BrewStakingError::NativeMintRequired
+        );
+        wrap_sol(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            initial_funding,
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
 
     let pool_state = &mut ctx.accounts.pool_state_account;
     pool_state.reward_amount = initial_funding;
     pool_state.total_staked = 0;
+    pool_state.version = CURRENT_ACCOUNT_VERSION;
 
-    // Trasfer deploy fee from creator to platform treasury
-    let creator_balance = ctx.accounts.creator.to_account_info().lamports();
-    require!(creator_balance > platform.deploy_fee, BrewStakingError::InsufficientDeployFee);
+    // Trasfer deploy fee from creator to platform treasury, in the fee token if
+    // the creator pays with it
+    let deploy_fee;
+    let mut deploy_fee_mint = Pubkey::default();
+    if let Some(creator_fee_token_account) = &ctx.accounts.creator_fee_token_account {
+        let (Some(fee_schedule), Some(treasury_fee_token_account)) = (
+            &fee_schedule,
+            &ctx.accounts.treasury_fee_token_account,
+        ) else {
+            return err!(BrewStakingError::WrongFeeToken);
+        };
+        require!(
+            fee_schedule.fee_mint != Pubkey::default() &&
+                creator_fee_token_account.mint == fee_schedule.fee_mint &&
+                treasury_fee_token_account.mint == fee_schedule.fee_mint,
+            BrewStakingError::WrongFeeToken
+        );
 
-    let cpi_program = ctx.accounts.system_program.to_account_info();
-    let cpi_accounts = system_program::Transfer {
-        from: ctx.accounts.creator.to_account_info(),
-        to: ctx.accounts.treasury.to_account_info(),
+        deploy_fee = (fee_schedule.deploy_fee_token * fee_rate) / PERCENT_PRECISION;
+        deploy_fee_mint = fee_schedule.fee_mint;
+        transfer_tokens(
+            creator_fee_token_account.to_account_info(),
+            treasury_fee_token_account.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            deploy_fee,
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    } else {
+        deploy_fee = (ctx.accounts.platform.deploy_fee * fee_rate) / PERCENT_PRECISION;
+
+        let creator_balance = ctx.accounts.creator.to_account_info().lamports();
+        require!(creator_balance > deploy_fee, BrewStakingError::InsufficientDeployFee);
+
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        system_program::transfer(cpi_ctx, deploy_fee)?;
+        pool_config.deploy_fee_paid = deploy_fee;
+    }
+
+    // List the pool in the platform registry and under its creator and stake mint
+    let platform_key = ctx.accounts.platform.key();
+    let entry = RegistryEntry {
+        pool: pool_config.key(),
+        creator: pool_config.owner,
+        stake_mint: pool_config.stake_mint,
+        reward_mint: pool_config.reward_mint,
+        status: POOL_STATUS_ACTIVE,
     };
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    system_program::transfer(cpi_ctx, platform.deploy_fee)?;
+    let position = register_pool(
+        &mut ctx.accounts.platform,
+        platform_key,
+        &mut ctx.accounts.registry_page,
+        entry
+    );
+    pool_config.registry_index = position;
+    append_to_index(
+        &mut ctx.accounts.creator_index,
+        &mut ctx.accounts.creator_index_page,
+        platform_key,
+        pool_config.owner,
+        position
+    );
+    append_to_index(
+        &mut ctx.accounts.stake_mint_index,
+        &mut ctx.accounts.stake_mint_index_page,
+        platform_key,
+        pool_config.stake_mint,
+        position
+    );
+
+    emit!(PoolCreated {
+        version: EVENT_VERSION,
+        pool: ctx.accounts.pool_config_account.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        creator: ctx.accounts.creator.key(),
+        pool_state: pool_state.key(),
+        stake_mint: ctx.accounts.stake_mint.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        initial_funding,
+        reward_rate,
+        duration,
+        stake_fee,
+        unstake_fee,
+        time_based,
+        deploy_fee,
+        deploy_fee_mint,
+    });
 
     Ok(())
 }
@@ -86,19 +210,96 @@ pub struct CreatePool<'info> {
     #[account(init, payer = creator, space = POOL_STATE_SIZE)]
     pub pool_state_account: Box<Account<'info, PoolState>>,
 
+    #[account(mut)]
     pub platform: Box<Account<'info, PlatformInfo>>,
 
     #[account(mut)]
     pub creator: Signer<'info>,
 
-    /// CHECK:
-    #[account(mut)]
+    /// CHECK: platform treasury, receives the deploy fee
+    #[account(mut, address = platform.treasury)]
     pub treasury: AccountInfo<'info>,
 
     pub stake_mint: Box<Account<'info, Mint>>,
 
     pub reward_mint: Box<Account<'info, Mint>>,
 
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = REGISTRY_PAGE_SIZE,
+        seeds = [
+            REGISTRY_SEED,
+            platform.key().as_ref(),
+            &registry_page_of(platform.pool_count).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = REGISTRY_INDEX_SIZE,
+        seeds = [REGISTRY_SEED, REGISTRY_CREATOR_SEED, platform.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub creator_index: Box<Account<'info, RegistryIndex>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = REGISTRY_INDEX_PAGE_SIZE,
+        seeds = [
+            REGISTRY_SEED,
+            REGISTRY_CREATOR_SEED,
+            platform.key().as_ref(),
+            creator.key().as_ref(),
+            &registry_page_of(creator_index.count).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub creator_index_page: Box<Account<'info, RegistryIndexPage>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = REGISTRY_INDEX_SIZE,
+        seeds = [REGISTRY_SEED, REGISTRY_MINT_SEED, platform.key().as_ref(), stake_mint.key().as_ref()],
+        bump
+    )]
+    pub stake_mint_index: Box<Account<'info, RegistryIndex>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = REGISTRY_INDEX_PAGE_SIZE,
+        seeds = [
+            REGISTRY_SEED,
+            REGISTRY_MINT_SEED,
+            platform.key().as_ref(),
+            stake_mint.key().as_ref(),
+            &registry_page_of(stake_mint_index.count).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub stake_mint_index_page: Box<Account<'info, RegistryIndexPage>>,
+
+    /// CHECK: FeeSchedule of the platform, may not exist yet
+    #[account(seeds = [FEE_SCHEDULE_SEED, platform.key().as_ref()], bump)]
+    pub fee_schedule: UncheckedAccount<'info>,
+
+    /// Partner discounts, left out by creators without a tier
+    #[account(seeds = [CREATOR_TIER_SEED, platform.key().as_ref(), creator.key().as_ref()], bump)]
+    pub creator_tier: Option<Box<Account<'info, CreatorTier>>>,
+
+    /// Only given when paying the deploy fee in the fee token
+    #[account(mut, token::authority = creator)]
+    pub creator_fee_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut, token::authority = treasury)]
+    pub treasury_fee_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
     // #[account(
     //     init,
     //     payer = creator,
@@ -121,12 +322,13 @@ pub struct CreatePool<'info> {
     #[account(mut)]
     pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
 
+    /// Left out for native SOL rewards, which are funded in lamports
     #[account(
         mut,
         token::authority = creator.key,
         // constraint = &creator_reward_token_vault.owner == creator.key
     )]
-    pub creator_reward_token_vault: Box<Account<'info, TokenAccount>>,
+    pub creator_reward_token_vault: Option<Box<Account<'info, TokenAccount>>>,
 
     pub system_program: Program<'info, System>,
 
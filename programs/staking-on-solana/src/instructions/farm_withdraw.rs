@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use amm_anchor::FarmWithdraw as RaydiumFarmWithdraw;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<FarmWithdraw>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.pool_config_account.farm_pool != Pubkey::default(),
+        BrewStakingError::FarmNotEnabled
+    );
+    require!(
+        amount <= ctx.accounts.pool_state_account.farm_staked,
+        BrewStakingError::UnstakeAmountTooHigh
+    );
+
+    let reward_a_before = ctx.accounts.pool_farm_reward_a_vault.amount;
+    let reward_b_before = ctx.accounts.pool_farm_reward_b_vault.amount;
+
+    // Bring LP back from the farm into the pool vault, harvesting pending rewards
+    let withdraw_accounts = RaydiumFarmWithdraw {
+        stake_pool: ctx.accounts.farm_pool.clone(),
+        pool_authority: ctx.accounts.farm_pool_authority.clone(),
+        staker_info: ctx.accounts.farm_staker_info.clone(),
+        staker_owner: ctx.accounts.admin.to_account_info(),
+        dest_lp_token: ctx.accounts.pool_stake_token_vault.to_account_info(),
+        vault_lp_token: ctx.accounts.farm_lp_vault.clone(),
+        dest_reward_token_a: ctx.accounts.pool_farm_reward_a_vault.to_account_info(),
+        vault_reward_token_a: ctx.accounts.farm_reward_a_vault.clone(),
+        dest_reward_token_b: ctx.accounts.pool_farm_reward_b_vault.to_account_info(),
+        vault_reward_token_b: ctx.accounts.farm_reward_b_vault.clone(),
+        clock: ctx.accounts.clock.clone(),
+        spl_token_program: ctx.accounts.spl_token_program.clone(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.farm_program.clone(), withdraw_accounts);
+    amm_anchor::farm_withdraw_v2(cpi_ctx, amount)?;
+
+    ctx.accounts.pool_farm_reward_a_vault.reload()?;
+    ctx.accounts.pool_farm_reward_b_vault.reload()?;
+    let harvested_a = ctx.accounts.pool_farm_reward_a_vault.amount - reward_a_before;
+    let harvested_b = ctx.accounts.pool_farm_reward_b_vault.amount - reward_b_before;
+
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    distribute_farm_rewards(pool_state, harvested_a, harvested_b);
+    pool_state.farm_staked -= amount;
+
+    emit!(FarmWithdrawn {
+        version: EVENT_VERSION,
+        pool: ctx.accounts.pool_config_account.key(),
+        seq: next_event_seq(pool_state),
+        slot: ctx.accounts.clock.slot,
+        amount,
+        harvested_a,
+        harvested_b,
+        farm_staked: pool_state.farm_staked,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FarmWithdraw<'info> {
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = pool_stake_token_vault,
+        has_one = pool_farm_reward_a_vault,
+        has_one = pool_farm_reward_b_vault,
+        has_one = farm_program,
+        has_one = farm_pool
+    )]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_farm_reward_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_farm_reward_b_vault: Box<Account<'info, TokenAccount>>,
+
+    // Raydium Farm Accounts
+    /// CHECK: Safe. farm program
+    pub farm_program: AccountInfo<'info>,
+    /// CHECK: Safe. farm pool Account
+    #[account(mut)]
+    pub farm_pool: AccountInfo<'info>,
+    /// CHECK: Safe. farm pool authority
+    pub farm_pool_authority: AccountInfo<'info>,
+    /// CHECK: Safe. staker info of the pool vault owner in the farm
+    #[account(mut)]
+    pub farm_staker_info: AccountInfo<'info>,
+    /// CHECK: Safe. farm lp token vault
+    #[account(mut)]
+    pub farm_lp_vault: AccountInfo<'info>,
+    /// CHECK: Safe. farm reward token A vault
+    #[account(mut)]
+    pub farm_reward_a_vault: AccountInfo<'info>,
+    /// CHECK: Safe. farm reward token B vault
+    #[account(mut)]
+    pub farm_reward_b_vault: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+
+/// Snapshot of a pool, returned through return data so clients can read it
+/// with a single simulated transaction. Points are slots, or seconds for
+/// time-based pools.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolInfo {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub time_based: bool,
+    pub current_point: u64,
+    pub start_point: u64,
+    pub end_point: u64,
+    /// Points left in the emission window, 0 before start is scheduled and after the end
+    pub time_left: u64,
+    /// Reward tokens emitted per point right now
+    pub emission_rate: u64,
+    pub total_staked: u64,
+    pub total_working: u64,
+    pub reward_amount: u64,
+    /// Reward tokens not yet owed to any staker
+    pub unallocated_rewards: u64,
+    /// Points the unallocated rewards last at the configured rate
+    pub reward_runway: u64,
+    pub insufficient_rewards: u64,
+    /// Yearly rewards over the staked amount, in reward tokens per stake token
+    pub apr_bps: u64,
+    pub stake_fee: u16,
+    pub unstake_fee: u16,
+    pub performance_fee: u64,
+}
+
+pub fn handler(ctx: Context<GetPoolInfo>) -> Result<PoolInfo> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    // Accrue in memory only, the accounts are not writable
+    update_pool(pool_config, pool_state)?;
+
+    let current = current_point(pool_config)?;
+    let start = start_point(pool_config);
+    let end = end_point(pool_config);
+
+    let emitting = start > 0 && current >= start && current < end;
+    let time_left = if start > 0 && current < end { end - current.max(start) } else { 0 };
+    let emission_rate = if emitting { reward_rate(pool_config) } else { 0 };
+
+    let owed_rewards = pool_state.should_total_paid.saturating_sub(pool_state.paid_rewards);
+    let unallocated_rewards = available_reward_tokens(pool_config, pool_state).saturating_sub(
+        owed_rewards
+    );
+    let reward_runway = if reward_rate(pool_config) > 0 {
+        unallocated_rewards / reward_rate(pool_config)
+    } else {
+        0
+    };
+
+    // Normalize decimals so the ratio compares whole tokens
+    let apr_bps = if pool_state.total_staked > 0 {
+        let yearly_rewards =
+            (emission_rate as u128) * (points_per_day(pool_config) as u128) * (DAYS_PER_YEAR as u128);
+        let apr =
+            (yearly_rewards *
+                (PERCENT_PRECISION as u128) *
+                (10u128).pow(pool_config.stake_mint_decimals as u32)) /
+            ((pool_state.total_staked as u128) *
+                (10u128).pow(pool_config.reward_mint_decimals as u32));
+        u64::try_from(apr).unwrap_or(u64::MAX)
+    } else {
+        0
+    };
+
+    Ok(PoolInfo {
+        pool: pool_config.key(),
+        owner: pool_config.owner,
+        stake_mint: pool_config.stake_mint,
+        reward_mint: pool_config.reward_mint,
+        time_based: pool_config.time_based,
+        current_point: current,
+        start_point: start,
+        end_point: end,
+        time_left,
+        emission_rate,
+        total_staked: pool_state.total_staked,
+        total_working: pool_state.total_working,
+        reward_amount: pool_state.reward_amount,
+        unallocated_rewards,
+        reward_runway,
+        insufficient_rewards: insufficient_rewards(pool_config, pool_state),
+        apr_bps,
+        stake_fee: pool_config.stake_fee,
+        unstake_fee: pool_config.unstake_fee,
+        performance_fee: performance_fee_of(&ctx.accounts.platform, pool_config),
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetPoolInfo<'info> {
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    pub platform: Account<'info, PlatformInfo>,
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+
+/// Snapshot of one user's position, returned through return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UserPosition {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub staked_amount: u64,
+    /// Boosted balance rewards are shared by
+    pub working_amount: u64,
+    pub pending_reward: u64,
+    pub farm_reward_a_pending: u64,
+    pub farm_reward_b_pending: u64,
+    /// Share of the pool's stake, in basis points
+    pub share_bps: u64,
+    pub deposit_slot: u64,
+    /// Claimed rewards still vesting
+    pub vesting_locked: u64,
+    /// Vested rewards ready for withdraw_vested
+    pub vesting_claimable: u64,
+    pub stake_fee: u16,
+    pub unstake_fee: u16,
+    /// Fee or early-exit penalty on unstaking the whole position now
+    pub exit_fee: u64,
+    pub performance_fee: u64,
+}
+
+pub fn handler(ctx: Context<GetUserPosition>) -> Result<UserPosition> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+
+    let pending_reward = pending_reward_of(pool_config, pool_state, user_info)?;
+
+    // Settle in memory only, the accounts are not writable
+    settle_farm_rewards(pool_state, user_info);
+    checkpoint_vesting(user_info, current_point(pool_config)?);
+
+    let share_bps = if pool_state.total_staked > 0 {
+        (((user_info.staked_amount as u128) * (PERCENT_PRECISION as u128)) /
+            (pool_state.total_staked as u128)) as u64
+    } else {
+        0
+    };
+
+    Ok(UserPosition {
+        pool: pool_config.key(),
+        user: ctx.accounts.user.key(),
+        staked_amount: user_info.staked_amount,
+        working_amount: user_info.working_amount,
+        pending_reward,
+        farm_reward_a_pending: user_info.farm_reward_a_owed,
+        farm_reward_b_pending: user_info.farm_reward_b_owed,
+        share_bps,
+        deposit_slot: user_info.deposit_slot,
+        vesting_locked: user_info.vesting_locked,
+        vesting_claimable: user_info.vesting_claimable,
+        stake_fee: pool_config.stake_fee,
+        unstake_fee: pool_config.unstake_fee,
+        exit_fee: unstake_fee_of(pool_config, user_info, user_info.staked_amount, Clock::get()?.slot),
+        performance_fee: performance_fee_of(&ctx.accounts.platform, pool_config),
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetUserPosition<'info> {
+    /// CHECK: Safe. Only used to derive the position address
+    pub user: AccountInfo<'info>,
+
+    #[account(seeds = [pool_config_account.key().as_ref(), user.key().as_ref()], bump)]
+    pub user_info: Account<'info, UserInfo>,
+
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    pub platform: Account<'info, PlatformInfo>,
+}
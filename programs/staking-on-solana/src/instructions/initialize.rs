@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{ self };
 
 use crate::state::*;
+use crate::events::*;
 
 pub fn handler(ctx: Context<Initialize>, deploy_fee: u64, performance_fee: u64) -> Result<()> {
     let platform = &mut ctx.accounts.platform;
@@ -9,6 +10,16 @@ pub fn handler(ctx: Context<Initialize>, deploy_fee: u64, performance_fee: u64)
     platform.treasury = ctx.accounts.treasury.key();
     platform.deploy_fee = deploy_fee;
     platform.performance_fee = performance_fee;
+    platform.version = CURRENT_ACCOUNT_VERSION;
+
+    emit!(PlatformInitialized {
+        version: EVENT_VERSION,
+        platform: platform.key(),
+        treasury: platform.treasury,
+        deploy_fee,
+        performance_fee,
+        slot: Clock::get()?.slot,
+    });
 
     Ok(())
 }
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, Mint, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Lock more tokens and/or push the lock end out. Locks can't be shortened, and
+// their end is rounded down to a week boundary.
+pub fn handler(ctx: Context<LockEscrow>, amount: u64, lock_end: u64) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let escrow_totals = &mut ctx.accounts.escrow_totals;
+    let now = Clock::get()?.unix_timestamp as u64;
+    let lock_end = (lock_end / ESCROW_WEEK_SECONDS) * ESCROW_WEEK_SECONDS;
+
+    // A lock stays counted in the totals it was opened in
+    require!(
+        escrow.totals == Pubkey::default() || escrow.totals == escrow_totals.key(),
+        BrewStakingError::WrongEscrow
+    );
+    if escrow.amount > 0 {
+        require!(escrow.lock_end > now, BrewStakingError::EscrowExpired);
+    }
+    require!(
+        lock_end > now && lock_end >= escrow.lock_end && lock_end <= now + MAX_ESCROW_LOCK_SECONDS,
+        BrewStakingError::InvalidLockEnd
+    );
+
+    transfer_tokens(
+        ctx.accounts.owner_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Swap the lock's old contribution to the totals for the new one
+    checkpoint_escrow_totals(escrow_totals, now);
+    if escrow.amount > 0 {
+        schedule_escrow_lock(escrow_totals, escrow.amount, escrow.lock_end, false);
+    }
+
+    escrow.owner = ctx.accounts.owner.key();
+    escrow.mint = ctx.accounts.mint.key();
+    escrow.version = CURRENT_ACCOUNT_VERSION;
+    escrow.totals = escrow_totals.key();
+    escrow.amount += amount;
+    escrow.lock_end = lock_end;
+
+    escrow_totals.total_locked += amount;
+    schedule_escrow_lock(escrow_totals, escrow.amount, escrow.lock_end, true);
+
+    emit!(EscrowLock {
+        version: EVENT_VERSION,
+        slot: Clock::get()?.slot,
+        escrow: escrow.key(),
+        owner: escrow.owner,
+        mint: escrow.mint,
+        amount: escrow.amount,
+        lock_end,
+        total_locked: escrow_totals.total_locked,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LockEscrow<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ESCROW_SIZE,
+        seeds = [ESCROW_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_TOTALS_SEED, escrow_totals.platform.as_ref(), mint.key().as_ref()],
+        bump,
+        has_one = vault
+    )]
+    pub escrow_totals: Box<Account<'info, EscrowTotals>>,
+
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, token::Token>,
+}
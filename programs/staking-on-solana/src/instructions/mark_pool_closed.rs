@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Retire a pool in the registry once it has no stakers left and either never
+// started or ran to its end.
+pub fn handler(ctx: Context<MarkPoolClosed>) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    let start = start_point(pool_config);
+    require!(
+        pool_state.total_staked == 0 &&
+            (start == 0 || current_point(pool_config)? >= end_point(pool_config)),
+        BrewStakingError::PoolStillActive
+    );
+
+    mark_registry_closed(&mut ctx.accounts.registry_page, pool_config, pool_config.key())?;
+
+    emit!(PoolMarkedClosed {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        registry_index: pool_config.registry_index,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MarkPoolClosed<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(has_one = owner)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    pub platform: Account<'info, PlatformInfo>,
+
+    #[account(
+        mut,
+        seeds = [
+            REGISTRY_SEED,
+            platform.key().as_ref(),
+            &registry_page_of(pool_config_account.registry_index).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<MigratePlatform>) -> Result<()> {
+    let migrated = migrate_account::<PlatformInfo>(
+        &ctx.accounts.platform,
+        PLATFORM_INFO_V1_SIZE,
+        PLATFORM_INFO_SIZE,
+        &ctx.accounts.treasury.to_account_info(),
+        &ctx.accounts.system_program.to_account_info()
+    )?;
+    if migrated {
+        let mut platform = PlatformInfo::try_deserialize(
+            &mut &ctx.accounts.platform.try_borrow_data()?[..]
+        )?;
+        platform.version = CURRENT_ACCOUNT_VERSION;
+        write_account(&ctx.accounts.platform, &platform)?;
+
+        emit!(PlatformMigrated {
+            version: EVENT_VERSION,
+            platform: ctx.accounts.platform.key(),
+            treasury: ctx.accounts.treasury.key(),
+            account_version: platform.version,
+            slot: Clock::get()?.slot,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePlatform<'info> {
+    /// CHECK: PlatformInfo in any layout version, checked by migrate_account
+    #[account(mut, seeds = [treasury.key().as_ref()], bump)]
+    pub platform: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub treasury: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<MigratePool>) -> Result<()> {
+    let owner = ctx.accounts.owner.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    let config_migrated = migrate_account::<PoolConfig>(
+        &ctx.accounts.pool_config_account,
+        POOL_CONFIG_V1_SIZE,
+        POOL_CONFIG_SIZE,
+        &owner,
+        &system_program
+    )?;
+
+    // Only readable in the current layout, a failed check rolls back the realloc
+    let mut pool_config = PoolConfig::try_deserialize(
+        &mut &ctx.accounts.pool_config_account.try_borrow_data()?[..]
+    )?;
+    require_keys_eq!(pool_config.owner, owner.key(), BrewStakingError::NotPoolOwner);
+    require_keys_eq!(
+        pool_config.state_addr,
+        ctx.accounts.pool_state_account.key(),
+        ErrorCode::ConstraintAddress
+    );
+    if config_migrated {
+        pool_config.version = CURRENT_ACCOUNT_VERSION;
+        write_account(&ctx.accounts.pool_config_account, &pool_config)?;
+    }
+
+    let state_migrated = migrate_account::<PoolState>(
+        &ctx.accounts.pool_state_account,
+        POOL_STATE_V1_SIZE,
+        POOL_STATE_SIZE,
+        &owner,
+        &system_program
+    )?;
+    if !config_migrated && !state_migrated {
+        return Ok(());
+    }
+
+    let mut pool_state = PoolState::try_deserialize(
+        &mut &ctx.accounts.pool_state_account.try_borrow_data()?[..]
+    )?;
+    if state_migrated {
+        // Pools from before boosting share rewards on the plain stake
+        pool_state.total_working = pool_state.total_staked;
+        pool_state.version = CURRENT_ACCOUNT_VERSION;
+    }
+    let seq = next_event_seq(&mut pool_state);
+    write_account(&ctx.accounts.pool_state_account, &pool_state)?;
+
+    emit!(PoolMigrated {
+        version: EVENT_VERSION,
+        pool: ctx.accounts.pool_config_account.key(),
+        seq,
+        slot: Clock::get()?.slot,
+        config_migrated,
+        state_migrated,
+        account_version: CURRENT_ACCOUNT_VERSION,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: PoolConfig in any layout version, checked by migrate_account
+    #[account(mut)]
+    pub pool_config_account: UncheckedAccount<'info>,
+
+    /// CHECK: PoolState in any layout version, checked against the config's state_addr
+    #[account(mut)]
+    pub pool_state_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
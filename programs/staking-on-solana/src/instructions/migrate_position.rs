@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Move the whole stake of an ended pool into its successor. Pending rewards of both
+// positions are paid out, stake and unstake fees are skipped and the performance fee
+// is charged once. Pools farming their LP pass the farm accounts as remaining
+// accounts, see withdraw_farm_shortfall.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, MigratePosition<'info>>) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let new_pool_config = &ctx.accounts.new_pool_config_account;
+    let new_pool_state = &mut ctx.accounts.new_pool_state_account;
+    let new_user_info = &mut ctx.accounts.new_user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
+
+    let current = current_point(pool_config)?;
+    require!(
+        start_point(pool_config) > 0 && current >= end_point(pool_config),
+        BrewStakingError::PoolNotEnded
+    );
+    require!(start_point(new_pool_config) > 0, BrewStakingError::PoolNotStarted);
+    require!(!new_pool_config.position_nft, BrewStakingError::PositionNftRequired);
+    require!(user_info.staked_amount > 0, BrewStakingError::UnstakeAmountTooSmall);
+
+    // Transfer Performance Fee from user to treasury
+    let user_balance = ctx.accounts.user.to_account_info().lamports();
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.user.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    // Settle and empty the old position
+    let _ = update_pool(pool_config, pool_state);
+
+    let amount = user_info.staked_amount;
+
+    // Pull back from the farm whatever the vault lacks to move the stake
+    withdraw_farm_shortfall(
+        pool_config,
+        pool_state,
+        amount,
+        &mut ctx.accounts.pool_stake_token_vault,
+        ctx.remaining_accounts,
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    settle_farm_rewards(pool_state, user_info);
+
+    let pending = take_pending_reward(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        0,
+        slot
+    )?;
+    if pending > 0 {
+        transfer_tokens(
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            ctx.accounts.user_reward_token_vault.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            pending,
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
+
+    transfer_tokens(
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.new_pool_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Migrations skip the unstake fee and keep the rewards still vesting in the old pool
+    debit_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        amount,
+        0,
+        performance_fee,
+        0,
+        0,
+        slot
+    );
+
+    emit!(PositionMigrated {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot,
+        user: ctx.accounts.user.key(),
+        successor_pool: new_pool_config.key(),
+        amount,
+    });
+
+    // Credit the stake to the position in the successor pool
+    let _ = update_pool(new_pool_config, new_pool_state);
+
+    settle_farm_rewards(new_pool_state, new_user_info);
+
+    if new_user_info.staked_amount > 0 {
+        let new_pending = take_pending_reward(
+            new_pool_config,
+            new_pool_state,
+            new_user_info,
+            ctx.accounts.user.key(),
+            0,
+            slot
+        )?;
+        if new_pending > 0 {
+            transfer_tokens(
+                ctx.accounts.new_pool_reward_token_vault.to_account_info(),
+                ctx.accounts.new_user_reward_token_vault.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                new_pending,
+                ctx.accounts.token_program.to_account_info()
+            )?;
+        }
+    }
+
+    // Migrations skip the stake fee too
+    let (ve_balance, ve_total) = ve_balances(
+        new_pool_config,
+        ctx.accounts.new_escrow.as_deref(),
+        ctx.accounts.new_escrow_totals.as_deref(),
+        ctx.accounts.user.key()
+    )?;
+    credit_stake(
+        new_pool_config,
+        new_pool_state,
+        new_user_info,
+        ctx.accounts.user.key(),
+        amount,
+        0,
+        0,
+        ve_balance,
+        ve_total,
+        slot
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(
+        has_one = pool_stake_token_vault,
+        has_one = pool_reward_token_vault,
+        constraint = pool_config_account.successor_pool == new_pool_config_account.key() @ BrewStakingError::NoSuccessorPool
+    )]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        constraint = new_pool_config_account.stake_mint == pool_config_account.stake_mint @ BrewStakingError::SuccessorMismatch,
+        constraint = new_pool_config_account.pool_stake_token_vault == new_pool_stake_token_vault.key(),
+        constraint = new_pool_config_account.pool_reward_token_vault == new_pool_reward_token_vault.key()
+    )]
+    pub new_pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = new_pool_config_account.state_addr)]
+    pub new_pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = USER_INFO_SIZE,
+        seeds = [new_pool_config_account.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub new_user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, user.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = new_user_reward_token_vault.owner == reward_payout_owner(&new_user_info, user.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub new_user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub new_pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub new_pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock boosting the user in boosted pools
+    /// CHECK: Escrow PDA of the user on the reward mint, may not exist, checked by ve_balances
+    pub new_escrow: Option<UncheckedAccount<'info>>,
+
+    pub new_escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, token::Token>,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<MigrateUserInfo>) -> Result<()> {
+    let migrated = migrate_account::<UserInfo>(
+        &ctx.accounts.user_info,
+        USER_INFO_V1_SIZE,
+        USER_INFO_SIZE,
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info()
+    )?;
+    if migrated {
+        let mut user_info = UserInfo::try_deserialize(
+            &mut &ctx.accounts.user_info.try_borrow_data()?[..]
+        )?;
+        // Matches the pool's total_working, set the same way by migrate_pool
+        user_info.working_amount = user_info.staked_amount;
+        user_info.version = CURRENT_ACCOUNT_VERSION;
+        write_account(&ctx.accounts.user_info, &user_info)?;
+
+        emit!(UserInfoMigrated {
+            version: EVENT_VERSION,
+            pool: ctx.accounts.pool_config_account.key(),
+            seq: next_event_seq(&mut ctx.accounts.pool_state_account),
+            slot: Clock::get()?.slot,
+            user: ctx.accounts.user.key(),
+            staked_amount: user_info.staked_amount,
+            account_version: user_info.version,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserInfo<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Pools are migrated before their users, see migrate_pool
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    /// CHECK: UserInfo in any layout version, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_info: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,19 +1,93 @@
 pub mod initialize;
+pub mod set_fee_schedule;
+pub mod set_creator_tier;
 pub mod create_pool;
 pub mod stake;
+pub mod stake_for;
+pub mod set_stake_for;
 pub mod unstake;
 pub mod compound_reward;
 pub mod claim_reward;
+pub mod set_claim_settings;
+pub mod set_vesting;
+pub mod set_early_exit_penalty;
+pub mod withdraw_vested;
+pub mod set_boost;
+pub mod create_escrow_totals;
+pub mod lock_escrow;
+pub mod withdraw_escrow;
+pub mod set_successor_pool;
+pub mod migrate_position;
+pub mod migrate_platform;
+pub mod migrate_pool;
+pub mod migrate_user_info;
+pub mod sweep_tokens;
+pub mod mark_pool_closed;
+pub mod cancel_pool;
+pub mod close_pool;
 pub mod start_reward;
+pub mod start_season;
 pub mod stop_reward;
 pub mod pending_reward;
+pub mod get_pool_info;
+pub mod get_user_position;
+pub mod zap_stake;
+pub mod unstake_and_swap;
+pub mod zap_liquidity_stake;
+pub mod unstake_remove_liquidity;
+pub mod set_farm;
+pub mod farm_deposit;
+pub mod farm_withdraw;
+pub mod claim_farm_reward;
+pub mod set_position_nft_mode;
+pub mod stake_position;
+pub mod unstake_position;
+pub mod claim_position_reward;
+pub mod claim_position_farm_reward;
 
 pub use initialize::*;
+pub use set_fee_schedule::*;
+pub use set_creator_tier::*;
 pub use create_pool::*;
 pub use stake::*;
+pub use stake_for::*;
+pub use set_stake_for::*;
 pub use unstake::*;
 pub use compound_reward::*;
 pub use claim_reward::*;
+pub use set_claim_settings::*;
+pub use set_vesting::*;
+pub use set_early_exit_penalty::*;
+pub use withdraw_vested::*;
+pub use set_boost::*;
+pub use create_escrow_totals::*;
+pub use lock_escrow::*;
+pub use withdraw_escrow::*;
+pub use set_successor_pool::*;
+pub use migrate_position::*;
+pub use migrate_platform::*;
+pub use migrate_pool::*;
+pub use migrate_user_info::*;
+pub use sweep_tokens::*;
+pub use mark_pool_closed::*;
+pub use cancel_pool::*;
+pub use close_pool::*;
 pub use start_reward::*;
+pub use start_season::*;
 pub use stop_reward::*;
 pub use pending_reward::*;
+pub use get_pool_info::*;
+pub use get_user_position::*;
+pub use zap_stake::*;
+pub use unstake_and_swap::*;
+pub use zap_liquidity_stake::*;
+pub use unstake_remove_liquidity::*;
+pub use set_farm::*;
+pub use farm_deposit::*;
+pub use farm_withdraw::*;
+pub use claim_farm_reward::*;
+pub use set_position_nft_mode::*;
+pub use stake_position::*;
+pub use unstake_position::*;
+pub use claim_position_reward::*;
+pub use claim_position_farm_reward::*;
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<SetBoost>, enabled: bool) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    // Working balances of existing stakers would go stale
+    require!(pool_state.total_staked == 0, BrewStakingError::PoolNotEmpty);
+
+    // Boosted pools weigh stakes by the locks in one platform's totals on the reward mint
+    pool_config.escrow_totals = match (&ctx.accounts.escrow_totals, enabled) {
+        (Some(escrow_totals), true) => {
+            require_keys_eq!(
+                escrow_totals.mint,
+                pool_config.reward_mint,
+                BrewStakingError::WrongEscrow
+            );
+            escrow_totals.key()
+        }
+        (None, true) => {
+            return err!(BrewStakingError::WrongEscrow);
+        }
+        (_, false) => Pubkey::default(),
+    };
+    pool_config.boost_enabled = enabled;
+
+    emit!(BoostSet {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        enabled,
+        escrow_totals: pool_config.escrow_totals,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBoost<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    /// Only needed to turn boosting on
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+}
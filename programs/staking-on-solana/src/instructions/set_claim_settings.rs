@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Pass the default key for either wallet to clear it.
+pub fn handler(
+    ctx: Context<SetClaimSettings>,
+    reward_recipient: Pubkey,
+    claim_delegate: Pubkey
+) -> Result<()> {
+    let user_info = &mut ctx.accounts.user_info;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    user_info.reward_recipient = reward_recipient;
+    user_info.claim_delegate = claim_delegate;
+
+    emit!(ClaimSettingsSet {
+        version: EVENT_VERSION,
+        pool: ctx.accounts.pool_config_account.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        user: ctx.accounts.user.key(),
+        reward_recipient,
+        claim_delegate,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetClaimSettings<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_info: Account<'info, UserInfo>,
+
+    // Positions of NFT pools are keyed on the position mint, whose keypair stays with
+    // the minter after the NFT is sold
+    #[account(constraint = !pool_config_account.position_nft @ BrewStakingError::PositionClaimSettings)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+// Zero discounts put the creator back on the standard fees.
+pub fn handler(
+    ctx: Context<SetCreatorTier>,
+    deploy_fee_discount_bps: u16,
+    performance_fee_discount_bps: u16
+) -> Result<()> {
+    require!(
+        (deploy_fee_discount_bps as u64) <= PERCENT_PRECISION &&
+            (performance_fee_discount_bps as u64) <= PERCENT_PRECISION,
+        BrewStakingError::InvalidFeeRate
+    );
+
+    let creator_tier = &mut ctx.accounts.creator_tier;
+
+    creator_tier.platform = ctx.accounts.platform.key();
+    creator_tier.creator = ctx.accounts.creator.key();
+    creator_tier.deploy_fee_discount_bps = deploy_fee_discount_bps;
+    creator_tier.performance_fee_discount_bps = performance_fee_discount_bps;
+    creator_tier.version = CURRENT_ACCOUNT_VERSION;
+
+    emit!(CreatorTierSet {
+        version: EVENT_VERSION,
+        platform: creator_tier.platform,
+        creator: creator_tier.creator,
+        deploy_fee_discount_bps,
+        performance_fee_discount_bps,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCreatorTier<'info> {
+    #[account(mut)]
+    pub treasury: Signer<'info>,
+
+    #[account(seeds = [treasury.key().as_ref()], bump, has_one = treasury)]
+    pub platform: Account<'info, PlatformInfo>,
+
+    /// CHECK: Safe. Creator the tier applies to
+    pub creator: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = CREATOR_TIER_SIZE,
+        seeds = [CREATOR_TIER_SEED, platform.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub creator_tier: Account<'info, CreatorTier>,
+
+    pub system_program: Program<'info, System>,
+}
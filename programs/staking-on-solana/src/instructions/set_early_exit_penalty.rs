@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// A zero `penalty_bps` turns the penalty off and charges the flat unstake fee again.
+pub fn handler(
+    ctx: Context<SetEarlyExitPenalty>,
+    penalty_bps: u16,
+    decay_days: u16,
+    redistribute: bool
+) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    require!(
+        penalty_bps <= MAX_EARLY_EXIT_PENALTY && (penalty_bps == 0 || decay_days > 0),
+        BrewStakingError::InvalidPenalty
+    );
+    require!(
+        !redistribute || pool_config.stake_mint == pool_config.reward_mint,
+        BrewStakingError::PenaltyRedistributionUnsupported
+    );
+
+    pool_config.early_exit_penalty_bps = penalty_bps;
+    pool_config.early_exit_penalty_days = decay_days;
+    pool_config.redistribute_penalties = redistribute;
+
+    emit!(EarlyExitPenaltySet {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        penalty_bps,
+        decay_days,
+        redistribute,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEarlyExitPenalty<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+}
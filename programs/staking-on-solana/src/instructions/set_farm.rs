@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<SetFarm>) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    require!(pool_state.farm_staked == 0, BrewStakingError::FarmStillStaked);
+
+    pool_config.farm_program = ctx.accounts.farm_program.key();
+    pool_config.farm_pool = ctx.accounts.farm_pool.key();
+    pool_config.pool_farm_reward_a_vault = ctx.accounts.pool_farm_reward_a_vault.key();
+    pool_config.pool_farm_reward_b_vault = ctx.accounts.pool_farm_reward_b_vault.key();
+
+    emit!(FarmSet {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        farm_program: pool_config.farm_program,
+        farm_pool: pool_config.farm_pool,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFarm<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner, constraint = pool_config_account.state_addr == pool_state_account.key())]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    /// CHECK: Safe. Raydium farm program
+    #[account(executable)]
+    pub farm_program: AccountInfo<'info>,
+
+    /// CHECK: Safe. Raydium farm pool the staked LP is forwarded to
+    #[account(owner = farm_program.key())]
+    pub farm_pool: AccountInfo<'info>,
+
+    pub pool_farm_reward_a_vault: Account<'info, TokenAccount>,
+
+    pub pool_farm_reward_b_vault: Account<'info, TokenAccount>,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+// Duration tiers scale the deploy fee of new pools, in lamports and in `fee_mint`.
+pub fn handler(
+    ctx: Context<SetFeeSchedule>,
+    duration_tiers: [DurationFeeTier; DURATION_FEE_TIERS],
+    fee_mint: Pubkey,
+    deploy_fee_token: u64,
+    cancel_refund_bps: u16
+) -> Result<()> {
+    require!((cancel_refund_bps as u64) <= PERCENT_PRECISION, BrewStakingError::InvalidFeeRate);
+
+    let fee_schedule = &mut ctx.accounts.fee_schedule;
+
+    fee_schedule.platform = ctx.accounts.platform.key();
+    fee_schedule.duration_tiers = duration_tiers;
+    fee_schedule.fee_mint = fee_mint;
+    fee_schedule.deploy_fee_token = deploy_fee_token;
+    fee_schedule.cancel_refund_bps = cancel_refund_bps;
+    fee_schedule.version = CURRENT_ACCOUNT_VERSION;
+
+    emit!(FeeScheduleSet {
+        version: EVENT_VERSION,
+        platform: fee_schedule.platform,
+        fee_mint,
+        deploy_fee_token,
+        cancel_refund_bps,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(mut)]
+    pub treasury: Signer<'info>,
+
+    #[account(seeds = [treasury.key().as_ref()], bump, has_one = treasury)]
+    pub platform: Account<'info, PlatformInfo>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = FEE_SCHEDULE_SIZE,
+        seeds = [FEE_SCHEDULE_SEED, platform.key().as_ref()],
+        bump
+    )]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
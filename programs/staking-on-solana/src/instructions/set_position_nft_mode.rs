@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<SetPositionNftMode>, enabled: bool) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    // Wallet positions and NFT positions can't share a pool
+    require!(pool_state.total_staked == 0, BrewStakingError::PoolNotEmpty);
+    require!(
+        !enabled || pool_config.vesting_period == 0,
+        BrewStakingError::VestingWithPositionNft
+    );
+
+    pool_config.position_nft = enabled;
+
+    emit!(PositionNftModeSet {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        enabled,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPositionNftMode<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+}
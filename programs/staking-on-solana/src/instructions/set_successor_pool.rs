@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<SetSuccessorPool>) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let successor = &ctx.accounts.successor_pool_config;
+
+    require!(successor.key() != pool_config.key(), BrewStakingError::SuccessorMismatch);
+    require!(successor.stake_mint == pool_config.stake_mint, BrewStakingError::SuccessorMismatch);
+
+    pool_config.successor_pool = successor.key();
+
+    emit!(SuccessorPoolSet {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        successor_pool: pool_config.successor_pool,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSuccessorPool<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    pub successor_pool_config: Account<'info, PoolConfig>,
+}
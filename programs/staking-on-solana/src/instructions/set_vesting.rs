@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// A zero `vesting_period` pays claimed rewards out right away again. Rewards already
+// locked keep vesting on their own schedule.
+pub fn handler(
+    ctx: Context<SetVesting>,
+    vesting_period: u64,
+    redistribute_forfeits: bool
+) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    require!(
+        vesting_period == 0 || !pool_config.position_nft,
+        BrewStakingError::VestingWithPositionNft
+    );
+
+    pool_config.vesting_period = vesting_period;
+    pool_config.redistribute_forfeits = redistribute_forfeits;
+
+    emit!(VestingSet {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        vesting_period,
+        redistribute_forfeits,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVesting<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+}
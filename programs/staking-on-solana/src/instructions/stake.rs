@@ -1,30 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{ self, TokenAccount, Transfer };
+use anchor_spl::token::{ self, spl_token, Mint, TokenAccount, Transfer };
 
 use crate::state::*;
 use crate::utils::*;
 use crate::error::*;
-use crate::events::*;
 
 pub fn handler(ctx: Context<Stake>, stake_amount: u64) -> Result<()> {
     let pool_config = &mut ctx.accounts.pool_config_account;
     let pool_state = &mut ctx.accounts.pool_state_account;
     let user_info = &mut ctx.accounts.user_info;
     let platform = &ctx.accounts.platform;
-
-    let clock = Clock::get()?;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
 
     // msg!("@current slot {}", clock.slot);
 
-    require!(
-        pool_config.start_slot > 0 && pool_config.start_slot < clock.slot,
-        BrewStakingError::PoolNotStarted
-    );
+    // Deposits are accepted once a start is scheduled, they earn from the start on
+    require!(start_point(pool_config) > 0, BrewStakingError::PoolNotStarted);
+    require!(!pool_config.position_nft, BrewStakingError::PositionNftRequired);
 
     // Transfer Performance Fee from user to treasury
     let user_balance = ctx.accounts.staker.to_account_info().lamports();
-    require!(user_balance > platform.performance_fee, BrewStakingError::InsufficientDeployFee);
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
 
     let cpi_program = ctx.accounts.system_program.to_account_info();
     let cpi_accounts = system_program::Transfer {
@@ -32,98 +30,101 @@ pub fn handler(ctx: Context<Stake>, stake_amount: u64) -> Result<()> {
         to: ctx.accounts.treasury.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    system_program::transfer(cpi_ctx, platform.performance_fee)?;
+    system_program::transfer(cpi_ctx, performance_fee)?;
 
     let _ = update_pool(pool_config, pool_state);
 
-    let precision_factor = get_precision_factor(pool_config);
-    // msg!("@precision_factor :  {}", precision_factor);
-    // msg!("@user_info.staked_amount :  {}", user_info.staked_amount);
+    settle_farm_rewards(pool_state, user_info);
 
     // If user already staked before
     if user_info.staked_amount > 0 {
-        // msg!("@@@ user staked before, claim reward");
         // Transfer the user his reward so far
-        let pending =
-            (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor -
-            user_info.reward_debt;
-        // msg!("@@@acc_token_per_share :  {}", pool_state.acc_token_per_share);
-        // msg!("@@@reward_debt :  {}", user_info.reward_debt);
-        // msg!("@@@pending :  {}", pending);
-
+        let pending = take_pending_reward(
+            pool_config,
+            pool_state,
+            user_info,
+            ctx.accounts.staker.key(),
+            0,
+            slot
+        )?;
         if pending > 0 {
-            require!(
-                available_reward_tokens(pool_config, pool_state) >= pending,
-                BrewStakingError::InsufficientReward
-            );
-
-            let cpi_accounts = Transfer {
-                from: ctx.accounts.pool_reward_token_vault.to_account_info(),
-                to: ctx.accounts.user_reward_token_vault.to_account_info(),
-                authority: ctx.accounts.admin.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token::transfer(cpi_ctx, pending)?;
-
-            pool_state.reward_amount -= pending;
-
-            // msg!("@@@total_earned before :  {}", pool_state.total_earned);
-            pool_state.total_earned = if pool_state.total_earned > pending {
-                pool_state.total_earned - pending
+            if let Some(user_reward_token_vault) = &ctx.accounts.user_reward_token_vault {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.pool_reward_token_vault.to_account_info(),
+                    to: user_reward_token_vault.to_account_info(),
+                    authority: ctx.accounts.admin.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token::transfer(cpi_ctx, pending)?;
             } else {
-                0
-            };
-            // msg!("@@@total_earned after :  {}", pool_state.total_earned);
-            // msg!("@@@paid_rewards before :  {}", pool_state.paid_rewards);
-            pool_state.paid_rewards += pending;
-            // msg!("@@@paid_rewards after :  {}", pool_state.paid_rewards);
-            emit!(RewardClaim {
-                claimer: ctx.accounts.staker.key(),
-                amount: pending,
-            });
+                let Some(payout_wallet) = &ctx.accounts.payout_wallet else {
+                    return err!(BrewStakingError::NativeSolAccountsMissing);
+                };
+                unwrap_sol(
+                    pool_config.reward_mint,
+                    ctx.accounts.pool_reward_token_vault.to_account_info(),
+                    ctx.accounts.wsol_account.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.native_mint.as_ref().map(|a| a.to_account_info()),
+                    ctx.accounts.admin.to_account_info(),
+                    ctx.accounts.staker.to_account_info(),
+                    payout_wallet.to_account_info(),
+                    pending,
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.system_program.to_account_info()
+                )?;
+            }
         }
     }
 
     // Transfer Token from staker to pool account
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.user_stake_token_vault.to_account_info(),
-        to: ctx.accounts.pool_stake_token_vault.to_account_info(),
-        authority: ctx.accounts.staker.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, stake_amount)?;
-
-    // Transfer stake fee from pool to pool owner
-    let stake_fee = (stake_amount * (pool_config.stake_fee as u64)) / PERCENT_PRECISION;
+    if let Some(user_stake_token_vault) = &ctx.accounts.user_stake_token_vault {
+        let cpi_accounts = Transfer {
+            from: user_stake_token_vault.to_account_info(),
+            to: ctx.accounts.pool_stake_token_vault.to_account_info(),
+            authority: ctx.accounts.staker.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, stake_amount)?;
+    } else {
+        // Native SOL pools take the stake in lamports
+        require_keys_eq!(
+            pool_config.stake_mint,
+            spl_token::native_mint::ID,
+            BrewStakingError::NativeMintRequired
+        );
+        wrap_sol(
+            ctx.accounts.staker.to_account_info(),
+            ctx.accounts.pool_stake_token_vault.to_account_info(),
+            stake_amount,
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.pool_stake_token_vault.to_account_info(),
-        to: ctx.accounts.creator_stake_token_vault.to_account_info(),
-        authority: ctx.accounts.admin.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, stake_fee)?;
-
-    // Update user and pool info
-    msg!("@stake_amount :  {}", stake_amount);
-    msg!("@stake_fee :  {}", stake_fee);
-    let real_amount = stake_amount - stake_fee;
-
-    user_info.staked_amount += real_amount;
-    msg!("@user_info.staked_amount :  {}", user_info.staked_amount);
-    user_info.reward_debt =
-        (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor;
-    msg!("@pool_state.acc_token_per_share :  {}", pool_state.acc_token_per_share);
-    msg!("@user_info.reward_debt :  {}", user_info.reward_debt);
-    pool_state.total_staked += real_amount;
-    msg!("@pool_state.total_staked :  {}", pool_state.total_staked);
-    emit!(Deposit {
-        staker: ctx.accounts.staker.key(),
-        amount: real_amount,
-    });
+    // Pay the stake fee and update user and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.staker.key()
+    )?;
+    settle_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.staker.key(),
+        stake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
 
     Ok(())
 }
@@ -158,11 +159,22 @@ pub struct Stake<'info> {
     #[account(mut)]
     pub pool_state_account: Account<'info, PoolState>,
 
+    /// Left out for native SOL pools, which take and pay lamports
     #[account(mut)]
-    pub user_stake_token_vault: Account<'info, TokenAccount>,
+    pub user_stake_token_vault: Option<Account<'info, TokenAccount>>,
 
-    #[account(mut)]
-    pub user_reward_token_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, staker.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Safe. Wallet native SOL rewards are paid to
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == reward_payout_owner(&user_info, staker.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
 
     #[account(mut)]
     pub pool_stake_token_vault: Account<'info, TokenAccount>,
@@ -176,6 +188,20 @@ pub struct Stake<'info> {
     #[account(mut)]
     pub treasury_stake_token_vault: Account<'info, TokenAccount>,
 
+    /// Vote-escrow lock boosting the user in boosted pools
+    /// CHECK: Escrow PDA of the user on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    /// Temporary wSOL account at [WSOL_SEED, staker], only for native SOL pools
+    /// CHECK: created and closed again by unwrap_sol
+    #[account(mut)]
+    pub wsol_account: Option<UncheckedAccount<'info>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub native_mint: Option<Account<'info, Mint>>,
+
     pub system_program: Program<'info, System>,
 
     pub token_program: Program<'info, token::Token>,
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+
+pub fn handler(ctx: Context<StakeFor>, stake_amount: u64) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
+
+    require!(!pool_config.stake_for_disabled, BrewStakingError::StakeForDisabled);
+    require!(start_point(pool_config) > 0, BrewStakingError::PoolNotStarted);
+    require!(!pool_config.position_nft, BrewStakingError::PositionNftRequired);
+
+    // Transfer Performance Fee from funder to treasury
+    let funder_balance = ctx.accounts.funder.to_account_info().lamports();
+    require!(funder_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.funder.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    let _ = update_pool(pool_config, pool_state);
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // Pay the beneficiary the reward accrued on the existing position
+    if user_info.staked_amount > 0 {
+        let pending = take_pending_reward(
+            pool_config,
+            pool_state,
+            user_info,
+            ctx.accounts.beneficiary.key(),
+            0,
+            slot
+        )?;
+        if pending > 0 {
+            transfer_tokens(
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.beneficiary_reward_token_vault.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                pending,
+                ctx.accounts.token_program.to_account_info()
+            )?;
+        }
+    }
+
+    // Transfer Token from funder to pool account
+    transfer_tokens(
+        ctx.accounts.funder_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.funder.to_account_info(),
+        stake_amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Pay the stake fee and update beneficiary and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.beneficiary.key()
+    )?;
+    settle_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.beneficiary.key(),
+        stake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeFor<'info> {
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = USER_INFO_SIZE,
+        seeds = [pool_config_account.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// CHECK: Safe. Wallet the position is opened for
+    pub beneficiary: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(has_one = pool_stake_token_vault, has_one = pool_reward_token_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut, token::authority = funder)]
+    pub funder_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = beneficiary_reward_token_vault.owner == reward_payout_owner(&user_info, beneficiary.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub beneficiary_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock boosting the beneficiary in boosted pools
+    /// CHECK: Escrow PDA of the beneficiary on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, token::Token>,
+}
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{ self, Mint, MintTo, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+
+// Positions of NFT pools are keyed on the position mint instead of the staker,
+// whoever holds the NFT controls the position. Passing a fresh mint keypair opens
+// a new position, passing an existing mint tops it up. Events name the position
+// mint as the staker.
+pub fn handler(ctx: Context<StakePosition>, stake_amount: u64) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let position_key = ctx.accounts.position_mint.key();
+    let slot = Clock::get()?.slot;
+
+    require!(pool_config.position_nft, BrewStakingError::PositionNftDisabled);
+    require!(start_point(pool_config) > 0, BrewStakingError::PoolNotStarted);
+
+    if ctx.accounts.position_mint.supply == 0 {
+        // New position, mint its NFT to the staker
+        let signer_seeds: &[&[&[u8]]] = &[&[POSITION_AUTHORITY_SEED, &[ctx.bumps.position_authority]]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.position_mint.to_account_info(),
+            to: ctx.accounts.position_token_account.to_account_info(),
+            authority: ctx.accounts.position_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token::mint_to(cpi_ctx, 1)?;
+    } else {
+        require!(ctx.accounts.position_token_account.amount == 1, BrewStakingError::NotPositionHolder);
+    }
+
+    // Transfer Performance Fee from staker to treasury
+    let user_balance = ctx.accounts.staker.to_account_info().lamports();
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.staker.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    let _ = update_pool(pool_config, pool_state);
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // Pay the holder the reward accrued on the position so far
+    if user_info.staked_amount > 0 {
+        let pending = take_pending_reward(pool_config, pool_state, user_info, position_key, 0, slot)?;
+        if pending > 0 {
+            transfer_tokens(
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.user_reward_token_vault.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                pending,
+                ctx.accounts.token_program.to_account_info()
+            )?;
+        }
+    }
+
+    // Transfer Token from staker to pool account
+    transfer_tokens(
+        ctx.accounts.user_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.staker.to_account_info(),
+        stake_amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Pay the stake fee and update position and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.position_token_account.owner
+    )?;
+    settle_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        position_key,
+        stake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakePosition<'info> {
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = USER_INFO_SIZE,
+        seeds = [pool_config_account.key().as_ref(), position_mint.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        mint::decimals = 0,
+        mint::authority = position_authority
+    )]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        associated_token::mint = position_mint,
+        associated_token::authority = staker
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Safe. Mint authority of all position NFTs
+    #[account(seeds = [POSITION_AUTHORITY_SEED], bump)]
+    pub position_authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(has_one = pool_stake_token_vault, has_one = pool_reward_token_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut)]
+    pub user_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Position rewards always go to whoever holds the NFT, claim settings don't apply
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == staker.key() @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock of the position holder, boosting the position in boosted pools
+    /// CHECK: Escrow PDA of the holder on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
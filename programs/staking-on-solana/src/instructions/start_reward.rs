@@ -2,35 +2,37 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{ self };
 
 use crate::state::*;
+use crate::utils::*;
 use crate::error::*;
 use crate::events::*;
 
-pub fn handler(ctx: Context<StartReward>) -> Result<()> {
+pub fn handler(ctx: Context<StartReward>, start_at: u64) -> Result<()> {
     let pool_config = &mut ctx.accounts.pool_config_account;
     let pool_state = &mut ctx.accounts.pool_state_account;
 
-    require!(pool_config.start_slot == 0, BrewStakingError::PoolAlreadyStarted);
+    require!(start_point(pool_config) == 0, BrewStakingError::PoolAlreadyStarted);
 
-    // msg!("insufficient rewadrs {}", insufficient_rewards(pool_config, pool_state));
-    // require!(
-    //     insufficient_rewards(pool_config, pool_state) == 0,
-    //     BrewStakingError::RewardNotDeposited
-    // );
-    let clock = Clock::get()?;
-    // CHECK
-    // Calculate start and end slot
-    pool_config.start_slot = clock.slot + 10;
-    pool_config.end_slot = pool_config.start_slot + (pool_config.duration as u64) * SLOTS_PER_DAY;
+    require_funded(pool_config, pool_state)?;
 
-    pool_state.last_reward_slot = pool_config.start_slot;
+    // Calculate start and end slot (or time)
+    let start = schedule_start(pool_config, start_at)?;
+    set_schedule(pool_config, start);
+
+    set_last_reward_point(pool_config, pool_state, start);
 
     // msg!("current slot {}", clock.slot);
     // msg!("pool_config.start_slot {}", pool_config.start_slot);
     // msg!("pool_config.end_slot {}", pool_config.end_slot);
 
     emit!(NewStartAndEndSlots {
-        start_slot: pool_config.start_slot,
-        end_slot: pool_config.end_slot,
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        time_based: pool_config.time_based,
+        start_point: start_point(pool_config),
+        end_point: end_point(pool_config),
+        reward_amount: pool_state.reward_amount,
     });
 
     Ok(())
@@ -42,10 +44,13 @@ pub struct StartReward<'info> {
     #[account(mut)]
     pub deployer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = pool_config_account.owner == deployer.key() @ BrewStakingError::NotPoolOwner
+    )]
     pub pool_config_account: Account<'info, PoolConfig>,
 
-    #[account(mut)]
+    #[account(mut, address = pool_config_account.state_addr)]
     pub pool_state_account: Account<'info, PoolState>,
 
     pub token_program: Program<'info, token::Token>,
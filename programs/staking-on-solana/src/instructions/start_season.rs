@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, spl_token, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Open a new reward season on an ended pool. Positions carry over, and nothing
+// accrues between the old end and the new start.
+pub fn handler(
+    ctx: Context<StartSeason>,
+    duration: u16,
+    reward_rate: u64,
+    funding: u64,
+    start_at: u64
+) -> Result<()> {
+    let pool_config = &mut ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+
+    require!(start_point(pool_config) > 0, BrewStakingError::PoolNotStarted);
+    require!(
+        current_point(pool_config)? >= end_point(pool_config),
+        BrewStakingError::PoolNotEnded
+    );
+    require!(duration > 0, BrewStakingError::InvalidDuration);
+
+    // Settle the ended season at its own rate before the schedule changes
+    update_pool(pool_config, pool_state)?;
+
+    if let Some(owner_reward_token_vault) = &ctx.accounts.owner_reward_token_vault {
+        transfer_tokens(
+            owner_reward_token_vault.to_account_info(),
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            funding,
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    } else {
+        // Native SOL rewards are funded in lamports
+        require_keys_eq!(
+            pool_config.reward_mint,
+            spl_token::native_mint::ID,
+            BrewStakingError::NativeMintRequired
+        );
+        wrap_sol(
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            funding,
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
+    pool_state.reward_amount += funding;
+
+    pool_config.duration = duration;
+    // A zero `reward_rate` spreads the new funding evenly over the duration
+    let reward_rate = if reward_rate == 0 {
+        derive_reward_rate(pool_config, funding)?
+    } else {
+        reward_rate
+    };
+    set_reward_rate(pool_config, reward_rate);
+
+    let start = schedule_start(pool_config, start_at)?;
+    set_schedule(pool_config, start);
+    // Rewards resume at the new start, update_pool has nothing to accrue before it
+    set_last_reward_point(pool_config, pool_state, start);
+
+    require_funded(pool_config, pool_state)?;
+
+    pool_config.season += 1;
+
+    emit!(SeasonStarted {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        season: pool_config.season,
+        time_based: pool_config.time_based,
+        start_point: start_point(pool_config),
+        end_point: end_point(pool_config),
+        reward_rate,
+        duration,
+        funding,
+        reward_amount: pool_state.reward_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StartSeason<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = pool_config_account.owner == owner.key() @ BrewStakingError::NotPoolOwner
+    )]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut, address = pool_config_account.pool_reward_token_vault)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Left out for native SOL rewards, which are funded in lamports
+    #[account(mut, token::mint = pool_config_account.reward_mint, token::authority = owner)]
+    pub owner_reward_token_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, token::Token>,
+}
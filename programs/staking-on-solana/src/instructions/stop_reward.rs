@@ -26,6 +26,8 @@ pub fn handler(ctx: Context<StopReward>) -> Result<()> {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         token::transfer(cpi_ctx, remain_rewards)?;
+        // Refunded tokens are no longer the pool's to pay or refund again
+        pool_state.reward_amount = pool_state.reward_amount.saturating_sub(remain_rewards);
 
         if pool_state.total_earned > remain_rewards {
             pool_state.total_earned -= remain_rewards;
@@ -34,11 +36,22 @@ pub fn handler(ctx: Context<StopReward>) -> Result<()> {
         }
     }
 
-    let clock = Clock::get()?;
-    pool_config.end_slot = clock.slot;
+    let current = current_point(pool_config)?;
+    if pool_config.time_based {
+        pool_config.end_time = current;
+    } else {
+        pool_config.end_slot = current;
+    }
 
     emit!(RewardsStop {
-        end_slot: pool_config.end_slot,
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        time_based: pool_config.time_based,
+        end_point: end_point(pool_config),
+        total_staked: pool_state.total_staked,
+        reward_amount: pool_state.reward_amount,
     });
 
     Ok(())
@@ -46,19 +59,27 @@ pub fn handler(ctx: Context<StopReward>) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct StopReward<'info> {
-    /// CHECK:
+    pub owner: Signer<'info>,
+
+    /// CHECK: vault authority, co-signs every transfer out of a vault
     #[account(mut)]
-    pub deployer: AccountInfo<'info>,
+    pub deployer: Signer<'info>,
 
+    // The pool ends here, so its new end point has to be written back
+    #[account(mut, has_one = owner, has_one = pool_reward_token_vault)]
     pub pool_config_account: Account<'info, PoolConfig>,
 
     #[account(mut)]
     pub pool_reward_token_vault: Account<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        token::mint = pool_config_account.reward_mint,
+        token::authority = owner
+    )]
     pub deployer_reward_token_vault: Account<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, address = pool_config_account.state_addr)]
     pub pool_state_account: Account<'info, PoolState>,
 
     pub token_program: Program<'info, token::Token>,
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+// Recover tokens sent to an account held by the vault authority by mistake.
+// Stray mints can be swept in full. The pool's stake and reward vaults only
+// give up what is left above staked principal and owed rewards.
+pub fn handler(ctx: Context<SweepTokens>, amount: u64) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let source = &ctx.accounts.source;
+    let source_key = source.key();
+
+    require!(amount > 0, BrewStakingError::SweepExceedsSurplus);
+
+    // Farm rewards in those vaults are owed to stakers whatever their mint
+    require!(
+        source_key != pool_config.pool_farm_reward_a_vault &&
+            source_key != pool_config.pool_farm_reward_b_vault,
+        BrewStakingError::SweepNotAllowed
+    );
+
+    let is_stake_vault = source_key == pool_config.pool_stake_token_vault;
+    let is_reward_vault = source_key == pool_config.pool_reward_token_vault;
+
+    if is_stake_vault || is_reward_vault {
+        let mut liabilities: u64 = 0;
+        if is_stake_vault {
+            // Whatever is deposited in the farm is not held here
+            liabilities += pool_state.total_staked - pool_state.farm_staked;
+        }
+        if is_reward_vault {
+            liabilities += pool_state.reward_amount + pool_state.total_vesting;
+        }
+        let surplus = source.amount.saturating_sub(liabilities);
+        require!(amount <= surplus, BrewStakingError::SweepExceedsSurplus);
+    } else {
+        // Any other account of these mints can't be told apart from user funds
+        require!(
+            source.mint != pool_config.stake_mint && source.mint != pool_config.reward_mint,
+            BrewStakingError::SweepNotAllowed
+        );
+    }
+
+    transfer_tokens(
+        source.to_account_info(),
+        ctx.accounts.destination.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    emit!(TokensSwept {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        source: source_key,
+        mint: source.mint,
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SweepTokens<'info> {
+    pub owner: Signer<'info>,
+
+    /// CHECK: vault authority, co-signs every transfer out of a vault
+    pub admin: Signer<'info>,
+
+    #[account(has_one = owner)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut, token::authority = admin)]
+    pub source: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = source.mint, token::authority = owner)]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, token::Token>,
+}
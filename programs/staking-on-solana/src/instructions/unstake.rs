@@ -1,24 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{ self, TokenAccount, Transfer };
+use anchor_spl::token::{ self, spl_token, Mint, TokenAccount, Transfer };
 
 use crate::state::*;
 use crate::utils::*;
 use crate::error::*;
-use crate::events::*;
 
-pub fn handler(ctx: Context<Unstake>, unstake_amount: u64) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+    unstake_amount: u64
+) -> Result<()> {
     let pool_config = &ctx.accounts.pool_config_account;
     let pool_state = &mut ctx.accounts.pool_state_account;
     let user_info = &mut ctx.accounts.user_info;
     let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
 
     require!(unstake_amount > 0, BrewStakingError::UnstakeAmountTooSmall);
-    require!(user_info.staked_amount > unstake_amount, BrewStakingError::UnstakeAmountTooHigh);
+    require!(user_info.staked_amount >= unstake_amount, BrewStakingError::UnstakeAmountTooHigh);
 
     // Transfer Performance Fee from user to treasury
     let user_balance = ctx.accounts.user.to_account_info().lamports();
-    require!(user_balance > platform.performance_fee, BrewStakingError::InsufficientDeployFee);
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
 
     let cpi_program = ctx.accounts.system_program.to_account_info();
     let cpi_accounts = system_program::Transfer {
@@ -26,82 +30,109 @@ pub fn handler(ctx: Context<Unstake>, unstake_amount: u64) -> Result<()> {
         to: ctx.accounts.treasury.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    system_program::transfer(cpi_ctx, platform.performance_fee)?;
+    system_program::transfer(cpi_ctx, performance_fee)?;
 
     let _ = update_pool(pool_config, pool_state);
 
-    let precision_factor = get_precision_factor(pool_config);
-
-    // If user already staked before
-    if user_info.staked_amount > 0 {
-        // Transfer the user his reward so far
-        let pending =
-            (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor -
-            user_info.reward_debt;
-
-        if pending > 0 {
-            require!(
-                available_reward_tokens(pool_config, pool_state) >= pending,
-                BrewStakingError::InsufficientReward
-            );
-
+    // Pull back from the farm whatever the vault lacks to pay out the exit
+    withdraw_farm_shortfall(
+        pool_config,
+        pool_state,
+        unstake_amount,
+        &mut ctx.accounts.pool_stake_token_vault,
+        ctx.remaining_accounts,
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // Transfer the user his reward so far
+    let pending = take_pending_reward(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        0,
+        slot
+    )?;
+    if pending > 0 {
+        if let Some(user_reward_token_vault) = &ctx.accounts.user_reward_token_vault {
             let cpi_accounts = Transfer {
                 from: ctx.accounts.pool_reward_token_vault.to_account_info(),
-                to: ctx.accounts.user_reward_token_vault.to_account_info(),
+                to: user_reward_token_vault.to_account_info(),
                 authority: ctx.accounts.admin.to_account_info(),
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
             let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
             token::transfer(cpi_ctx, pending)?;
-
-            pool_state.reward_amount -= pending;
-
-            pool_state.total_earned = if pool_state.total_earned > pending {
-                pool_state.total_earned - pending
-            } else {
-                0
+        } else {
+            let Some(payout_wallet) = &ctx.accounts.payout_wallet else {
+                return err!(BrewStakingError::NativeSolAccountsMissing);
             };
-            pool_state.paid_rewards += pending;
-
-            emit!(RewardClaim {
-                claimer: ctx.accounts.user.key(),
-                amount: pending,
-            });
+            unwrap_sol(
+                pool_config.reward_mint,
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.wsol_account.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.native_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                payout_wallet.to_account_info(),
+                pending,
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info()
+            )?;
         }
     }
 
-    let mut real_amount = unstake_amount;
-    if user_info.staked_amount < unstake_amount {
-        real_amount = user_info.staked_amount;
-    }
-    // Transfer unstake fee from pool to pool owner
-    let unstake_fee = (real_amount * (pool_config.unstake_fee as u64)) / PERCENT_PRECISION;
-
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.pool_stake_token_vault.to_account_info(),
-        to: ctx.accounts.creator_stake_token_vault.to_account_info(),
-        authority: ctx.accounts.admin.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, unstake_fee)?;
+    // Pay the unstake fee or early-exit penalty and update user and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.user.key()
+    )?;
+    let unstake_fee = settle_unstake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        unstake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_reward_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
 
     // Transfer unstake amount from pool to user
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.pool_stake_token_vault.to_account_info(),
-        to: ctx.accounts.user_stake_token_vault.to_account_info(),
-        authority: ctx.accounts.admin.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, real_amount - unstake_fee)?;
-
-    // Update user and pool info
-    user_info.staked_amount -= real_amount;
-    user_info.reward_debt =
-        (user_info.staked_amount * pool_state.acc_token_per_share) / precision_factor;
-
-    pool_state.total_staked -= real_amount;
+    if let Some(user_stake_token_vault) = &ctx.accounts.user_stake_token_vault {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool_stake_token_vault.to_account_info(),
+            to: user_stake_token_vault.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::transfer(cpi_ctx, unstake_amount - unstake_fee)?;
+    } else {
+        unwrap_sol(
+            pool_config.stake_mint,
+            ctx.accounts.pool_stake_token_vault.to_account_info(),
+            ctx.accounts.wsol_account.as_ref().map(|a| a.to_account_info()),
+            ctx.accounts.native_mint.as_ref().map(|a| a.to_account_info()),
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            unstake_amount - unstake_fee,
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info()
+        )?;
+    }
 
     Ok(())
 }
@@ -129,11 +160,22 @@ pub struct Unstake<'info> {
     #[account(mut)]
     pub user_info: Account<'info, UserInfo>,
 
+    /// Left out for native SOL pools, which take and pay lamports
     #[account(mut)]
-    pub user_stake_token_vault: Account<'info, TokenAccount>,
+    pub user_stake_token_vault: Option<Account<'info, TokenAccount>>,
 
-    #[account(mut)]
-    pub user_reward_token_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, user.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Safe. Wallet native SOL rewards are paid to
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == reward_payout_owner(&user_info, user.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
 
     #[account(mut)]
     pub pool_stake_token_vault: Account<'info, TokenAccount>,
@@ -147,6 +189,20 @@ pub struct Unstake<'info> {
     #[account(mut)]
     pub treasury_stake_token_vault: Account<'info, TokenAccount>,
 
+    /// Vote-escrow lock boosting the user in boosted pools
+    /// CHECK: Escrow PDA of the user on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    /// Temporary wSOL account at [WSOL_SEED, user], only for native SOL pools
+    /// CHECK: created and closed again by unwrap_sol
+    #[account(mut)]
+    pub wsol_account: Option<UncheckedAccount<'info>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub native_mint: Option<Account<'info, Mint>>,
+
     pub token_program: Program<'info, token::Token>,
 
     pub system_program: Program<'info, System>,
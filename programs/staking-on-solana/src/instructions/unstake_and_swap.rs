@@ -0,0 +1,318 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, TokenAccount };
+use amm_anchor::{ SwapBaseIn, SwapBaseOut };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+
+// Number of remaining accounts describing the reward -> output route:
+// the amm program followed by the 14 amm/serum accounts of `SwapBaseIn`.
+pub const REWARD_ROUTE_ACCOUNTS: usize = 15;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnstakeAndSwap<'info>>,
+    unstake_amount: u64,
+    minimum_amount_out: u64,
+    exact_out: bool,
+    swap_rewards: bool
+) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
+
+    require!(unstake_amount > 0, BrewStakingError::UnstakeAmountTooSmall);
+    require!(user_info.staked_amount >= unstake_amount, BrewStakingError::UnstakeAmountTooHigh);
+    // The reward route comes first, the farm accounts for a shortfall after it
+    let route_accounts = if swap_rewards { REWARD_ROUTE_ACCOUNTS } else { 0 };
+    require!(
+        ctx.remaining_accounts.len() == route_accounts ||
+            ctx.remaining_accounts.len() == route_accounts + FARM_WITHDRAW_ACCOUNTS,
+        BrewStakingError::UnexpectedAccount
+    );
+    require!(
+        !swap_rewards || pool_config.vesting_period == 0,
+        BrewStakingError::RewardsVesting
+    );
+    // Rewards owed to another recipient can't be swapped on the user's behalf
+    require!(
+        !swap_rewards || reward_payout_owner(user_info, ctx.accounts.user.key()) == ctx.accounts.user.key(),
+        BrewStakingError::WrongRewardRecipient
+    );
+
+    // Transfer Performance Fee from user to treasury
+    let user_balance = ctx.accounts.user.to_account_info().lamports();
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.user.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    let _ = update_pool(pool_config, pool_state);
+
+    // Pull back from the farm whatever the vault lacks to pay out the exit
+    withdraw_farm_shortfall(
+        pool_config,
+        pool_state,
+        unstake_amount,
+        &mut ctx.accounts.pool_stake_token_vault,
+        &ctx.remaining_accounts[route_accounts..],
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // Transfer the user his reward so far
+    let pending = take_pending_reward(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        0,
+        slot
+    )?;
+    if pending > 0 {
+        transfer_tokens(
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            ctx.accounts.user_reward_token_vault.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            pending,
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
+
+    // Pay the unstake fee or early-exit penalty and update user and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.user.key()
+    )?;
+    let unstake_fee = settle_unstake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        unstake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_reward_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Transfer unstake amount from pool to user
+    let returned_amount = unstake_amount - unstake_fee;
+    transfer_tokens(
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.user_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        returned_amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    let user_output_balance_before = ctx.accounts.user_output_token_account.amount;
+
+    // Swap the returned stake token into the output token
+    if exact_out {
+        let swap_accounts = SwapBaseOut {
+            amm: ctx.accounts.amm.clone(),
+            amm_authority: ctx.accounts.amm_authority.clone(),
+            amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+            amm_target_orders: ctx.accounts.amm_target_orders.clone(),
+            pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+            pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+            serum_program: ctx.accounts.serum_program.clone(),
+            serum_market: ctx.accounts.serum_market.clone(),
+            serum_bids: ctx.accounts.serum_bids.clone(),
+            serum_asks: ctx.accounts.serum_asks.clone(),
+            serum_event_queue: ctx.accounts.serum_event_queue.clone(),
+            serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
+            serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
+            serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
+            user_source_token_account: ctx.accounts.user_stake_token_vault.to_account_info(),
+            user_destination_token_account: ctx.accounts.user_output_token_account.to_account_info(),
+            user_source_owner: ctx.accounts.user.to_account_info(),
+            spl_token_program: ctx.accounts.spl_token_program.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.amm_program.clone(), swap_accounts);
+        amm_anchor::swap_base_out(cpi_ctx, returned_amount, minimum_amount_out)?;
+    } else {
+        let swap_accounts = SwapBaseIn {
+            amm: ctx.accounts.amm.clone(),
+            amm_authority: ctx.accounts.amm_authority.clone(),
+            amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+            amm_target_orders: ctx.accounts.amm_target_orders.clone(),
+            pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+            pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+            serum_program: ctx.accounts.serum_program.clone(),
+            serum_market: ctx.accounts.serum_market.clone(),
+            serum_bids: ctx.accounts.serum_bids.clone(),
+            serum_asks: ctx.accounts.serum_asks.clone(),
+            serum_event_queue: ctx.accounts.serum_event_queue.clone(),
+            serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
+            serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
+            serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
+            user_source_token_account: ctx.accounts.user_stake_token_vault.to_account_info(),
+            user_destination_token_account: ctx.accounts.user_output_token_account.to_account_info(),
+            user_source_owner: ctx.accounts.user.to_account_info(),
+            spl_token_program: ctx.accounts.spl_token_program.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.amm_program.clone(), swap_accounts);
+        amm_anchor::swap_base_in(cpi_ctx, returned_amount, 0)?;
+    }
+
+    // Swap the paid reward through the route passed in remaining accounts
+    if swap_rewards && pending > 0 {
+        let route = ctx.remaining_accounts;
+        let swap_accounts = SwapBaseIn {
+            amm: route[1].clone(),
+            amm_authority: route[2].clone(),
+            amm_open_orders: route[3].clone(),
+            amm_target_orders: route[4].clone(),
+            pool_coin_token_account: route[5].clone(),
+            pool_pc_token_account: route[6].clone(),
+            serum_program: route[7].clone(),
+            serum_market: route[8].clone(),
+            serum_bids: route[9].clone(),
+            serum_asks: route[10].clone(),
+            serum_event_queue: route[11].clone(),
+            serum_coin_vault_account: route[12].clone(),
+            serum_pc_vault_account: route[13].clone(),
+            serum_vault_signer: route[14].clone(),
+            user_source_token_account: ctx.accounts.user_reward_token_vault.to_account_info(),
+            user_destination_token_account: ctx.accounts.user_output_token_account.to_account_info(),
+            user_source_owner: ctx.accounts.user.to_account_info(),
+            spl_token_program: ctx.accounts.spl_token_program.clone(),
+        };
+        let cpi_ctx = CpiContext::new(route[0].clone(), swap_accounts);
+        amm_anchor::swap_base_in(cpi_ctx, pending, 0)?;
+    }
+
+    // Slippage is checked once over everything the user received
+    ctx.accounts.user_output_token_account.reload()?;
+    let amount_out = ctx.accounts.user_output_token_account.amount - user_output_balance_before;
+    require!(amount_out >= minimum_amount_out, BrewStakingError::SlippageExceeded);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeAndSwap<'info> {
+    /// CHECK:
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(has_one = pool_stake_token_vault, has_one = pool_reward_token_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(mut, token::authority = user)]
+    pub user_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, user.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = user)]
+    pub user_output_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock boosting the user in boosted pools
+    /// CHECK: Escrow PDA of the user on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    pub system_program: Program<'info, System>,
+
+    // Raydium Swap Accounts
+    /// CHECK: Safe. amm program
+    pub amm_program: AccountInfo<'info>,
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: AccountInfo<'info>,
+    /// CHECK: Safe. Amm authority Account
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: Safe. amm target_orders Account
+    #[account(mut)]
+    pub amm_target_orders: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_coin Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_coin_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_pc Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_pc_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. serum dex program id
+    pub serum_program: AccountInfo<'info>,
+    /// CHECK: Safe. serum market Account. serum_dex program is the owner.
+    #[account(mut)]
+    pub serum_market: AccountInfo<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub serum_bids: AccountInfo<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub serum_asks: AccountInfo<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub serum_event_queue: AccountInfo<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub serum_coin_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub serum_pc_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. vault_signer Account
+    #[account(mut)]
+    pub serum_vault_signer: AccountInfo<'info>,
+
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, Mint, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnstakePosition<'info>>,
+    unstake_amount: u64
+) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let position_key = ctx.accounts.position_mint.key();
+    let slot = Clock::get()?.slot;
+
+    require!(unstake_amount > 0, BrewStakingError::UnstakeAmountTooSmall);
+    require!(user_info.staked_amount >= unstake_amount, BrewStakingError::UnstakeAmountTooHigh);
+
+    // Transfer Performance Fee from holder to treasury
+    let user_balance = ctx.accounts.holder.to_account_info().lamports();
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.holder.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    let _ = update_pool(pool_config, pool_state);
+
+    // Pull back from the farm whatever the vault lacks to pay out the exit
+    withdraw_farm_shortfall(
+        pool_config,
+        pool_state,
+        unstake_amount,
+        &mut ctx.accounts.pool_stake_token_vault,
+        ctx.remaining_accounts,
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // Transfer the holder the reward of the position so far
+    let pending = take_pending_reward(pool_config, pool_state, user_info, position_key, 0, slot)?;
+    if pending > 0 {
+        transfer_tokens(
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            ctx.accounts.user_reward_token_vault.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            pending,
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
+
+    // Pay the unstake fee or early-exit penalty and update position and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.position_token_account.owner
+    )?;
+    let unstake_fee = settle_unstake(
+        pool_config,
+        pool_state,
+        user_info,
+        position_key,
+        unstake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_reward_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Transfer unstake amount from pool to holder
+    transfer_tokens(
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.user_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        unstake_amount - unstake_fee,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakePosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = position_token_account.mint == position_mint.key() &&
+            position_token_account.owner == holder.key() &&
+            position_token_account.amount == 1 @ BrewStakingError::NotPositionHolder
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), position_mint.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(has_one = pool_stake_token_vault, has_one = pool_reward_token_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(mut)]
+    pub user_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Position rewards always go to whoever holds the NFT, claim settings don't apply
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == holder.key() @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock of the position holder, boosting the position in boosted pools
+    /// CHECK: Escrow PDA of the holder on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,271 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, TokenAccount };
+use amm_anchor::Withdraw as AmmWithdraw;
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnstakeRemoveLiquidity<'info>>,
+    unstake_amount: u64,
+    minimum_coin_out: u64,
+    minimum_pc_out: u64
+) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
+
+    require!(unstake_amount > 0, BrewStakingError::UnstakeAmountTooSmall);
+    require!(user_info.staked_amount >= unstake_amount, BrewStakingError::UnstakeAmountTooHigh);
+
+    // Transfer Performance Fee from user to treasury
+    let user_balance = ctx.accounts.user.to_account_info().lamports();
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.user.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    let _ = update_pool(pool_config, pool_state);
+
+    // Pull back from the farm whatever the vault lacks to pay out the exit
+    withdraw_farm_shortfall(
+        pool_config,
+        pool_state,
+        unstake_amount,
+        &mut ctx.accounts.pool_stake_token_vault,
+        ctx.remaining_accounts,
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // Transfer the user his reward so far
+    let pending = take_pending_reward(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        0,
+        slot
+    )?;
+    if pending > 0 {
+        transfer_tokens(
+            ctx.accounts.pool_reward_token_vault.to_account_info(),
+            ctx.accounts.user_reward_token_vault.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            pending,
+            ctx.accounts.token_program.to_account_info()
+        )?;
+    }
+
+    // Pay the unstake fee or early-exit penalty and update user and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.user.key()
+    )?;
+    let unstake_fee = settle_unstake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.user.key(),
+        unstake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_reward_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Transfer unstaked LP token from pool to user
+    let returned_amount = unstake_amount - unstake_fee;
+    transfer_tokens(
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.user_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        returned_amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Burn the LP token for both sides of the pair
+    let coin_balance_before = ctx.accounts.user_coin_token_account.amount;
+    let pc_balance_before = ctx.accounts.user_pc_token_account.amount;
+
+    let withdraw_accounts = AmmWithdraw {
+        amm: ctx.accounts.amm.clone(),
+        amm_authority: ctx.accounts.amm_authority.clone(),
+        amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+        amm_target_orders: ctx.accounts.amm_target_orders.clone(),
+        lp_mint: ctx.accounts.lp_mint.to_account_info(),
+        pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+        pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+        pool_withdraw_queue: ctx.accounts.pool_withdraw_queue.clone(),
+        pool_temp_lp_token_account: ctx.accounts.pool_temp_lp_token_account.clone(),
+        serum_program: ctx.accounts.serum_program.clone(),
+        serum_market: ctx.accounts.serum_market.clone(),
+        serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
+        serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
+        serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
+        user_lp_token_account: ctx.accounts.user_stake_token_vault.to_account_info(),
+        user_coin_token_account: ctx.accounts.user_coin_token_account.to_account_info(),
+        user_pc_token_account: ctx.accounts.user_pc_token_account.to_account_info(),
+        user_owner: ctx.accounts.user.to_account_info(),
+        serum_event_q: ctx.accounts.serum_event_queue.clone(),
+        serum_bids: ctx.accounts.serum_bids.clone(),
+        serum_asks: ctx.accounts.serum_asks.clone(),
+        spl_token_program: ctx.accounts.spl_token_program.clone(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.amm_program.clone(), withdraw_accounts);
+    amm_anchor::withdraw(cpi_ctx, returned_amount)?;
+
+    ctx.accounts.user_coin_token_account.reload()?;
+    ctx.accounts.user_pc_token_account.reload()?;
+    require!(
+        ctx.accounts.user_coin_token_account.amount - coin_balance_before >= minimum_coin_out,
+        BrewStakingError::SlippageExceeded
+    );
+    require!(
+        ctx.accounts.user_pc_token_account.amount - pc_balance_before >= minimum_pc_out,
+        BrewStakingError::SlippageExceeded
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeRemoveLiquidity<'info> {
+    /// CHECK:
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(has_one = pool_stake_token_vault, has_one = pool_reward_token_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(
+        mut,
+        token::mint = pool_config_account.stake_mint,
+        token::authority = user
+    )]
+    pub user_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, user.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = user)]
+    pub user_coin_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = user)]
+    pub user_pc_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock boosting the user in boosted pools
+    /// CHECK: Escrow PDA of the user on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    pub system_program: Program<'info, System>,
+
+    // Raydium Liquidity Accounts
+    /// CHECK: Safe. amm program
+    pub amm_program: AccountInfo<'info>,
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: AccountInfo<'info>,
+    /// CHECK: Safe. Amm authority Account
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: Safe. amm target_orders Account
+    #[account(mut)]
+    pub amm_target_orders: AccountInfo<'info>,
+    /// CHECK: Safe. LP mint of the amm, must be the pool stake mint
+    #[account(mut, address = pool_config_account.stake_mint)]
+    pub lp_mint: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_coin Amm Account to withdraw FROM,
+    #[account(mut)]
+    pub pool_coin_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_pc Amm Account to withdraw FROM,
+    #[account(mut)]
+    pub pool_pc_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. withdraw queue Account
+    #[account(mut)]
+    pub pool_withdraw_queue: AccountInfo<'info>,
+    /// CHECK: Safe. token_temp_lp Account
+    #[account(mut)]
+    pub pool_temp_lp_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. serum dex program id
+    pub serum_program: AccountInfo<'info>,
+    /// CHECK: Safe. serum market Account. serum_dex program is the owner.
+    #[account(mut)]
+    pub serum_market: AccountInfo<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub serum_bids: AccountInfo<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub serum_asks: AccountInfo<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub serum_event_queue: AccountInfo<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub serum_coin_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub serum_pc_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. vault_signer Account
+    pub serum_vault_signer: AccountInfo<'info>,
+
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, Mint, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<WithdrawEscrow>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let escrow_totals = &mut ctx.accounts.escrow_totals;
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    require!(now >= escrow.lock_end, BrewStakingError::EscrowLocked);
+
+    transfer_tokens(
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.owner_token_account.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        escrow.amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // The expired lock already left the running sums at its week boundary
+    checkpoint_escrow_totals(escrow_totals, now);
+    escrow_totals.total_locked -= escrow.amount;
+
+    emit!(EscrowWithdraw {
+        version: EVENT_VERSION,
+        slot: Clock::get()?.slot,
+        escrow: escrow.key(),
+        owner: escrow.owner,
+        mint: escrow.mint,
+        amount: escrow.amount,
+        total_locked: escrow_totals.total_locked,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawEscrow<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK:
+    pub admin: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [ESCROW_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        has_one = owner
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(mut, address = escrow.totals, has_one = vault)]
+    pub escrow_totals: Box<Account<'info, EscrowTotals>>,
+
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, TokenAccount };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+use crate::events::*;
+
+pub fn handler(ctx: Context<WithdrawVested>) -> Result<()> {
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+
+    checkpoint_vesting(user_info, current_point(pool_config)?);
+
+    let amount = user_info.vesting_claimable;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    transfer_tokens(
+        ctx.accounts.pool_reward_token_vault.to_account_info(),
+        ctx.accounts.user_reward_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    user_info.vesting_claimable = 0;
+    pool_state.total_vesting -= amount;
+
+    emit!(VestedWithdraw {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        user: ctx.accounts.owner.key(),
+        amount,
+        user_locked: user_info.vesting_locked,
+        total_vesting: pool_state.total_vesting,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    pub claimer: Signer<'info>,
+
+    /// CHECK: Safe. Owner of the position, only used to derive it
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK:
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [pool_config_account.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = can_claim_for(&user_info, owner.key(), claimer.key()) @ BrewStakingError::NotPositionOwner
+    )]
+    pub user_info: Account<'info, UserInfo>,
+
+    #[account(has_one = pool_reward_token_vault)]
+    pub pool_config_account: Account<'info, PoolConfig>,
+
+    #[account(mut, address = pool_config_account.state_addr)]
+    pub pool_state_account: Account<'info, PoolState>,
+
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, owner.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+}
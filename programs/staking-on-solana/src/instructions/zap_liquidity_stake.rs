@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{ self, TokenAccount };
+use amm_anchor::{ Deposit as AmmDeposit, SwapBaseIn };
+
+use crate::state::*;
+use crate::utils::*;
+use crate::error::*;
+
+// Raydium deposit base side: the side whose amount is taken as exact
+const BASE_SIDE_COIN: u64 = 0;
+const BASE_SIDE_PC: u64 = 1;
+
+pub fn handler(
+    ctx: Context<ZapLiquidityStake>,
+    amount_in: u64,
+    input_is_coin: bool,
+    minimum_lp_out: u64
+) -> Result<()> {
+    require!(
+        start_point(&ctx.accounts.pool_config_account) > 0,
+        BrewStakingError::PoolNotStarted
+    );
+    require!(
+        !ctx.accounts.pool_config_account.position_nft,
+        BrewStakingError::PositionNftRequired
+    );
+    require!(amount_in > 1, BrewStakingError::DepositAmountIsTooLow);
+
+    let (source, destination) = if input_is_coin {
+        (
+            ctx.accounts.user_coin_token_account.to_account_info(),
+            ctx.accounts.user_pc_token_account.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.user_pc_token_account.to_account_info(),
+            ctx.accounts.user_coin_token_account.to_account_info(),
+        )
+    };
+    let coin_balance_before = ctx.accounts.user_coin_token_account.amount;
+    let pc_balance_before = ctx.accounts.user_pc_token_account.amount;
+
+    // Swap half of the input into the other side of the pair
+    let swap_amount = amount_in / 2;
+    let swap_base_in_accounts = SwapBaseIn {
+        amm: ctx.accounts.amm.clone(),
+        amm_authority: ctx.accounts.amm_authority.clone(),
+        amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+        amm_target_orders: ctx.accounts.amm_target_orders.clone(),
+        pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+        pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+        serum_program: ctx.accounts.serum_program.clone(),
+        serum_market: ctx.accounts.serum_market.clone(),
+        serum_bids: ctx.accounts.serum_bids.clone(),
+        serum_asks: ctx.accounts.serum_asks.clone(),
+        serum_event_queue: ctx.accounts.serum_event_queue.clone(),
+        serum_coin_vault_account: ctx.accounts.serum_coin_vault_account.clone(),
+        serum_pc_vault_account: ctx.accounts.serum_pc_vault_account.clone(),
+        serum_vault_signer: ctx.accounts.serum_vault_signer.clone(),
+        user_source_token_account: source,
+        user_destination_token_account: destination,
+        user_source_owner: ctx.accounts.staker.to_account_info(),
+        spl_token_program: ctx.accounts.spl_token_program.clone(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.amm_program.clone(), swap_base_in_accounts);
+    amm_anchor::swap_base_in(cpi_ctx, swap_amount, 0)?;
+
+    ctx.accounts.user_coin_token_account.reload()?;
+    ctx.accounts.user_pc_token_account.reload()?;
+
+    // The swapped side is known exactly, so it is used as the deposit base
+    let (max_coin_amount, max_pc_amount, base_side) = if input_is_coin {
+        (
+            amount_in - swap_amount,
+            ctx.accounts.user_pc_token_account.amount - pc_balance_before,
+            BASE_SIDE_PC,
+        )
+    } else {
+        (
+            ctx.accounts.user_coin_token_account.amount - coin_balance_before,
+            amount_in - swap_amount,
+            BASE_SIDE_COIN,
+        )
+    };
+
+    // Add both sides as liquidity and receive the LP (stake) token
+    let user_lp_balance_before = ctx.accounts.user_stake_token_vault.amount;
+
+    let deposit_accounts = AmmDeposit {
+        amm: ctx.accounts.amm.clone(),
+        amm_authority: ctx.accounts.amm_authority.clone(),
+        amm_open_orders: ctx.accounts.amm_open_orders.clone(),
+        amm_target_orders: ctx.accounts.amm_target_orders.clone(),
+        lp_mint: ctx.accounts.lp_mint.to_account_info(),
+        pool_coin_token_account: ctx.accounts.pool_coin_token_account.clone(),
+        pool_pc_token_account: ctx.accounts.pool_pc_token_account.clone(),
+        serum_market: ctx.accounts.serum_market.clone(),
+        serum_event_queue: ctx.accounts.serum_event_queue.clone(),
+        user_coin_token_account: ctx.accounts.user_coin_token_account.to_account_info(),
+        user_pc_token_account: ctx.accounts.user_pc_token_account.to_account_info(),
+        user_lp_token_account: ctx.accounts.user_stake_token_vault.to_account_info(),
+        user_owner: ctx.accounts.staker.to_account_info(),
+        spl_token_program: ctx.accounts.spl_token_program.clone(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.amm_program.clone(), deposit_accounts);
+    amm_anchor::deposit(cpi_ctx, max_coin_amount, max_pc_amount, base_side)?;
+
+    ctx.accounts.user_stake_token_vault.reload()?;
+    let stake_amount = ctx.accounts.user_stake_token_vault.amount - user_lp_balance_before;
+    require!(stake_amount >= minimum_lp_out, BrewStakingError::SlippageExceeded);
+
+    let pool_config = &ctx.accounts.pool_config_account;
+    let pool_state = &mut ctx.accounts.pool_state_account;
+    let user_info = &mut ctx.accounts.user_info;
+    let platform = &ctx.accounts.platform;
+    let performance_fee = performance_fee_of(platform, pool_config);
+    let slot = Clock::get()?.slot;
+
+    // Transfer Performance Fee from user to treasury
+    let user_balance = ctx.accounts.staker.to_account_info().lamports();
+    require!(user_balance > performance_fee, BrewStakingError::InsufficientDeployFee);
+
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.staker.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    system_program::transfer(cpi_ctx, performance_fee)?;
+
+    let _ = update_pool(pool_config, pool_state);
+
+    settle_farm_rewards(pool_state, user_info);
+
+    // If user already staked before
+    if user_info.staked_amount > 0 {
+        // Transfer the user his reward so far
+        let payout = take_pending_reward(
+            pool_config,
+            pool_state,
+            user_info,
+            ctx.accounts.staker.key(),
+            0,
+            slot
+        )?;
+        if payout > 0 {
+            transfer_tokens(
+                ctx.accounts.pool_reward_token_vault.to_account_info(),
+                ctx.accounts.user_reward_token_vault.to_account_info(),
+                ctx.accounts.admin.to_account_info(),
+                payout,
+                ctx.accounts.token_program.to_account_info()
+            )?;
+        }
+    }
+
+    // Transfer minted LP token from staker to pool account
+    transfer_tokens(
+        ctx.accounts.user_stake_token_vault.to_account_info(),
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.staker.to_account_info(),
+        stake_amount,
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    // Pay the stake fee and update user and pool info
+    let (ve_balance, ve_total) = ve_balances(
+        pool_config,
+        ctx.accounts.escrow.as_deref(),
+        ctx.accounts.escrow_totals.as_deref(),
+        ctx.accounts.staker.key()
+    )?;
+    settle_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        ctx.accounts.staker.key(),
+        stake_amount,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot,
+        ctx.accounts.pool_stake_token_vault.to_account_info(),
+        ctx.accounts.creator_stake_token_vault.to_account_info(),
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.token_program.to_account_info()
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ZapLiquidityStake<'info> {
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = USER_INFO_SIZE,
+        seeds = [pool_config_account.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub user_info: Box<Account<'info, UserInfo>>,
+
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub admin: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub treasury: AccountInfo<'info>,
+
+    pub platform: Box<Account<'info, PlatformInfo>>,
+
+    #[account(mut, has_one = pool_stake_token_vault, has_one = pool_reward_token_vault)]
+    pub pool_config_account: Box<Account<'info, PoolConfig>>,
+
+    #[account(mut)]
+    pub pool_state_account: Box<Account<'info, PoolState>>,
+
+    #[account(mut, token::authority = staker)]
+    pub user_coin_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = staker)]
+    pub user_pc_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = pool_config_account.stake_mint,
+        token::authority = staker
+    )]
+    pub user_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_token_vault.owner == reward_payout_owner(&user_info, staker.key()) @ BrewStakingError::WrongRewardRecipient
+    )]
+    pub user_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_reward_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_stake_token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Vote-escrow lock boosting the user in boosted pools
+    /// CHECK: Escrow PDA of the user on the reward mint, may not exist, checked by ve_balances
+    pub escrow: Option<UncheckedAccount<'info>>,
+
+    pub escrow_totals: Option<Box<Account<'info, EscrowTotals>>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, token::Token>,
+
+    // Raydium Liquidity Accounts
+    /// CHECK: Safe. amm program
+    pub amm_program: AccountInfo<'info>,
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: AccountInfo<'info>,
+    /// CHECK: Safe. Amm authority Account
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: Safe. amm target_orders Account
+    #[account(mut)]
+    pub amm_target_orders: AccountInfo<'info>,
+    /// CHECK: Safe. LP mint of the amm, must be the pool stake mint
+    #[account(mut, address = pool_config_account.stake_mint)]
+    pub lp_mint: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_coin Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_coin_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. pool_token_pc Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub pool_pc_token_account: AccountInfo<'info>,
+    /// CHECK: Safe. serum dex program id
+    pub serum_program: AccountInfo<'info>,
+    /// CHECK: Safe. serum market Account. serum_dex program is the owner.
+    #[account(mut)]
+    pub serum_market: AccountInfo<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub serum_bids: AccountInfo<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub serum_asks: AccountInfo<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub serum_event_queue: AccountInfo<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub serum_coin_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub serum_pc_vault_account: AccountInfo<'info>,
+    /// CHECK: Safe. vault_signer Account
+    #[account(mut)]
+    pub serum_vault_signer: AccountInfo<'info>,
+
+    /// CHECK: Safe. The spl token program
+    #[account(address = spl_token::ID)]
+    pub spl_token_program: AccountInfo<'info>,
+}
@@ -22,21 +22,54 @@ pub mod staking_on_solana {
         instructions::initialize::handler(ctx, deploy_fee, performance_fee)
     }
 
+    pub fn set_fee_schedule(
+        ctx: Context<SetFeeSchedule>,
+        duration_tiers: [state::DurationFeeTier; 4],
+        fee_mint: Pubkey,
+        deploy_fee_token: u64,
+        cancel_refund_bps: u16
+    ) -> Result<()> {
+        instructions::set_fee_schedule::handler(
+            ctx,
+            duration_tiers,
+            fee_mint,
+            deploy_fee_token,
+            cancel_refund_bps
+        )
+    }
+
+    pub fn set_creator_tier(
+        ctx: Context<SetCreatorTier>,
+        deploy_fee_discount_bps: u16,
+        performance_fee_discount_bps: u16
+    ) -> Result<()> {
+        instructions::set_creator_tier::handler(
+            ctx,
+            deploy_fee_discount_bps,
+            performance_fee_discount_bps
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_pool(
         ctx: Context<CreatePool>,
         stake_fee: u16,
         unstake_fee: u16,
         initial_funding: u64,
-        reward_per_slot: u64,
-        duration: u16
+        reward_rate: u64,
+        duration: u16,
+        use_stable_swap: bool,
+        time_based: bool
     ) -> Result<()> {
         instructions::create_pool::handler(
             ctx,
             stake_fee,
             unstake_fee,
             initial_funding,
-            reward_per_slot,
-            duration
+            reward_rate,
+            duration,
+            use_stable_swap,
+            time_based
         )
     }
 
@@ -44,27 +77,225 @@ pub mod staking_on_solana {
         instructions::stake::handler(ctx, stake_amount)
     }
 
-    pub fn unstake(ctx: Context<Unstake>, unstake_amount: u64) -> Result<()> {
+    pub fn stake_for(ctx: Context<StakeFor>, stake_amount: u64) -> Result<()> {
+        instructions::stake_for::handler(ctx, stake_amount)
+    }
+
+    pub fn set_stake_for(ctx: Context<SetStakeFor>, enabled: bool) -> Result<()> {
+        instructions::set_stake_for::handler(ctx, enabled)
+    }
+
+    pub fn zap_stake(
+        ctx: Context<ZapStake>,
+        amount_in: u64,
+        minimum_amount_out: u64
+    ) -> Result<()> {
+        instructions::zap_stake::handler(ctx, amount_in, minimum_amount_out)
+    }
+
+    pub fn unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+        unstake_amount: u64
+    ) -> Result<()> {
         instructions::unstake::handler(ctx, unstake_amount)
     }
 
+    pub fn unstake_and_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakeAndSwap<'info>>,
+        unstake_amount: u64,
+        minimum_amount_out: u64,
+        exact_out: bool,
+        swap_rewards: bool
+    ) -> Result<()> {
+        instructions::unstake_and_swap::handler(
+            ctx,
+            unstake_amount,
+            minimum_amount_out,
+            exact_out,
+            swap_rewards
+        )
+    }
+
+    pub fn zap_liquidity_stake(
+        ctx: Context<ZapLiquidityStake>,
+        amount_in: u64,
+        input_is_coin: bool,
+        minimum_lp_out: u64
+    ) -> Result<()> {
+        instructions::zap_liquidity_stake::handler(ctx, amount_in, input_is_coin, minimum_lp_out)
+    }
+
+    pub fn unstake_remove_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakeRemoveLiquidity<'info>>,
+        unstake_amount: u64,
+        minimum_coin_out: u64,
+        minimum_pc_out: u64
+    ) -> Result<()> {
+        instructions::unstake_remove_liquidity::handler(
+            ctx,
+            unstake_amount,
+            minimum_coin_out,
+            minimum_pc_out
+        )
+    }
+
     pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
         instructions::claim_reward::handler(ctx)
     }
 
-    pub fn start_reward(ctx: Context<StartReward>) -> Result<()> {
-        instructions::start_reward::handler(ctx)
+    pub fn set_claim_settings(
+        ctx: Context<SetClaimSettings>,
+        reward_recipient: Pubkey,
+        claim_delegate: Pubkey
+    ) -> Result<()> {
+        instructions::set_claim_settings::handler(ctx, reward_recipient, claim_delegate)
+    }
+
+    pub fn set_vesting(
+        ctx: Context<SetVesting>,
+        vesting_period: u64,
+        redistribute_forfeits: bool
+    ) -> Result<()> {
+        instructions::set_vesting::handler(ctx, vesting_period, redistribute_forfeits)
+    }
+
+    pub fn set_early_exit_penalty(
+        ctx: Context<SetEarlyExitPenalty>,
+        penalty_bps: u16,
+        decay_days: u16,
+        redistribute: bool
+    ) -> Result<()> {
+        instructions::set_early_exit_penalty::handler(ctx, penalty_bps, decay_days, redistribute)
+    }
+
+    pub fn withdraw_vested(ctx: Context<WithdrawVested>) -> Result<()> {
+        instructions::withdraw_vested::handler(ctx)
+    }
+
+    pub fn set_boost(ctx: Context<SetBoost>, enabled: bool) -> Result<()> {
+        instructions::set_boost::handler(ctx, enabled)
+    }
+
+    pub fn create_escrow_totals(ctx: Context<CreateEscrowTotals>) -> Result<()> {
+        instructions::create_escrow_totals::handler(ctx)
+    }
+
+    pub fn lock_escrow(ctx: Context<LockEscrow>, amount: u64, lock_end: u64) -> Result<()> {
+        instructions::lock_escrow::handler(ctx, amount, lock_end)
+    }
+
+    pub fn withdraw_escrow(ctx: Context<WithdrawEscrow>) -> Result<()> {
+        instructions::withdraw_escrow::handler(ctx)
+    }
+
+    pub fn set_successor_pool(ctx: Context<SetSuccessorPool>) -> Result<()> {
+        instructions::set_successor_pool::handler(ctx)
+    }
+
+    pub fn migrate_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigratePosition<'info>>
+    ) -> Result<()> {
+        instructions::migrate_position::handler(ctx)
+    }
+
+    pub fn migrate_platform(ctx: Context<MigratePlatform>) -> Result<()> {
+        instructions::migrate_platform::handler(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool::handler(ctx)
+    }
+
+    pub fn migrate_user_info(ctx: Context<MigrateUserInfo>) -> Result<()> {
+        instructions::migrate_user_info::handler(ctx)
+    }
+
+    pub fn sweep_tokens(ctx: Context<SweepTokens>, amount: u64) -> Result<()> {
+        instructions::sweep_tokens::handler(ctx, amount)
+    }
+
+    pub fn mark_pool_closed(ctx: Context<MarkPoolClosed>) -> Result<()> {
+        instructions::mark_pool_closed::handler(ctx)
+    }
+
+    pub fn cancel_pool(ctx: Context<CancelPool>) -> Result<()> {
+        instructions::cancel_pool::handler(ctx)
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool::handler(ctx)
+    }
+
+    pub fn start_reward(ctx: Context<StartReward>, start_at: u64) -> Result<()> {
+        instructions::start_reward::handler(ctx, start_at)
+    }
+
+    pub fn start_season(
+        ctx: Context<StartSeason>,
+        duration: u16,
+        reward_rate: u64,
+        funding: u64,
+        start_at: u64
+    ) -> Result<()> {
+        instructions::start_season::handler(ctx, duration, reward_rate, funding, start_at)
     }
 
     pub fn stop_reward(ctx: Context<StopReward>) -> Result<()> {
         instructions::stop_reward::handler(ctx)
     }
 
-    pub fn compound_reward(ctx: Context<CompoundReward>) -> Result<()> {
-        instructions::compound_reward::handler(ctx)
+    pub fn compound_reward(ctx: Context<CompoundReward>, minimum_amount_out: u64) -> Result<()> {
+        instructions::compound_reward::handler(ctx, minimum_amount_out)
+    }
+
+    pub fn set_farm(ctx: Context<SetFarm>) -> Result<()> {
+        instructions::set_farm::handler(ctx)
+    }
+
+    pub fn farm_deposit(ctx: Context<FarmDeposit>, amount: u64) -> Result<()> {
+        instructions::farm_deposit::handler(ctx, amount)
+    }
+
+    pub fn farm_withdraw(ctx: Context<FarmWithdraw>, amount: u64) -> Result<()> {
+        instructions::farm_withdraw::handler(ctx, amount)
+    }
+
+    pub fn claim_farm_reward(ctx: Context<ClaimFarmReward>) -> Result<()> {
+        instructions::claim_farm_reward::handler(ctx)
+    }
+
+    pub fn set_position_nft_mode(ctx: Context<SetPositionNftMode>, enabled: bool) -> Result<()> {
+        instructions::set_position_nft_mode::handler(ctx, enabled)
+    }
+
+    pub fn stake_position(ctx: Context<StakePosition>, stake_amount: u64) -> Result<()> {
+        instructions::stake_position::handler(ctx, stake_amount)
+    }
+
+    pub fn unstake_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakePosition<'info>>,
+        unstake_amount: u64
+    ) -> Result<()> {
+        instructions::unstake_position::handler(ctx, unstake_amount)
+    }
+
+    pub fn claim_position_reward(ctx: Context<ClaimPositionReward>) -> Result<()> {
+        instructions::claim_position_reward::handler(ctx)
+    }
+
+    pub fn claim_position_farm_reward(ctx: Context<ClaimPositionFarmReward>) -> Result<()> {
+        instructions::claim_position_farm_reward::handler(ctx)
     }
 
     pub fn pending_reward(ctx: Context<PendingReward>) -> Result<u64> {
         instructions::pending_reward::handler(ctx)
     }
+
+    pub fn get_pool_info(ctx: Context<GetPoolInfo>) -> Result<PoolInfo> {
+        instructions::get_pool_info::handler(ctx)
+    }
+
+    pub fn get_user_position(ctx: Context<GetUserPosition>) -> Result<UserPosition> {
+        instructions::get_user_position::handler(ctx)
+    }
 }
@@ -1,3 +1,33 @@
 pub const PERCENT_PRECISION: u64 = 10000;
 pub const SLOTS_PER_DAY: u64 = 216000;
+pub const SECONDS_PER_DAY: u64 = 86400;
+pub const START_DELAY_SLOTS: u64 = 10;
+pub const START_DELAY_SECONDS: u64 = 4;
+pub const MAX_START_HORIZON_DAYS: u64 = 30;
 pub const MAX_FEE: u16 = 2000;
+pub const MAX_EARLY_EXIT_PENALTY: u16 = 5000;
+pub const FARM_REWARD_PRECISION: u128 = 1_000_000_000_000;
+// Remaining accounts for pulling LP back from the farm on exit: the farm program, farm
+// pool, pool authority, staker info, LP vault, reward A and B vaults, the pool's own
+// farm reward A and B vaults and the clock sysvar.
+pub const FARM_WITHDRAW_ACCOUNTS: usize = 10;
+pub const DAYS_PER_YEAR: u64 = 365;
+pub const POSITION_AUTHORITY_SEED: &[u8] = b"position_authority";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const ESCROW_TOTALS_SEED: &[u8] = b"escrow_totals";
+pub const MAX_ESCROW_LOCK_SECONDS: u64 = 4 * DAYS_PER_YEAR * SECONDS_PER_DAY;
+pub const ESCROW_WEEK_SECONDS: u64 = 7 * SECONDS_PER_DAY;
+pub const ESCROW_SCHEDULE_WEEKS: usize = ((MAX_ESCROW_LOCK_SECONDS / ESCROW_WEEK_SECONDS) as usize) + 2;
+pub const BOOST_BASE_BPS: u64 = 4000;
+pub const WSOL_SEED: &[u8] = b"wsol";
+pub const REGISTRY_SEED: &[u8] = b"registry";
+pub const REGISTRY_CREATOR_SEED: &[u8] = b"creator";
+pub const REGISTRY_MINT_SEED: &[u8] = b"stake_mint";
+pub const REGISTRY_PAGE_LEN: usize = 32;
+pub const POOL_STATUS_ACTIVE: u8 = 0;
+pub const POOL_STATUS_CLOSED: u8 = 1;
+pub const FEE_SCHEDULE_SEED: &[u8] = b"fee_schedule";
+pub const CREATOR_TIER_SEED: &[u8] = b"creator_tier";
+pub const DURATION_FEE_TIERS: usize = 4;
+pub const CURRENT_ACCOUNT_VERSION: u8 = 2;
+pub const ACCOUNT_RESERVED_BYTES: usize = 64;
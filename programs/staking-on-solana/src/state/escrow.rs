@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+// Vote-escrow lock of one wallet on one mint. Its weight decays linearly
+// to zero at `lock_end`, from `amount` for a lock of MAX_ESCROW_LOCK_SECONDS.
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub lock_end: u64,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// EscrowTotals the lock is counted in
+    pub totals: Pubkey,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 32],
+}
+
+pub const ESCROW_SIZE: usize = 8 + Escrow::INIT_SPACE;
+
+// Aggregate of all escrows on one mint under one platform. Locks end on week
+// boundaries, so the locks expiring each week drop out of the running sums at once.
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowTotals {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// Tokens held for all escrows, expired ones included until withdrawn
+    pub total_locked: u64,
+    /// Sum of amount * lock_end over the locks still running at `checkpoint_time`
+    pub locked_end_sum: u128,
+    /// Sum of amount over the locks still running at `checkpoint_time`
+    pub active_locked: u64,
+    pub checkpoint_time: u64,
+    /// Amount expiring at each week boundary, indexed by week modulo ESCROW_SCHEDULE_WEEKS
+    pub expiring: [u64; ESCROW_SCHEDULE_WEEKS],
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Platform whose treasury created the totals
+    pub platform: Pubkey,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 32],
+}
+
+pub const ESCROW_TOTALS_SIZE: usize = 8 + EscrowTotals::INIT_SPACE;
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct DurationFeeTier {
+    /// Pools running at least this many days pay this tier's rate
+    pub min_days: u16,
+    /// Deploy fee rate in bps of the base fee, PERCENT_PRECISION is the full fee
+    pub fee_bps: u16,
+}
+
+// Platform-wide fee rules at [FEE_SCHEDULE_SEED, platform], managed by the treasury.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeSchedule {
+    pub platform: Pubkey,
+    /// Unused tiers have `fee_bps` zero and are skipped
+    pub duration_tiers: [DurationFeeTier; DURATION_FEE_TIERS],
+    /// SPL token the deploy fee can be paid in, the default key disables it
+    pub fee_mint: Pubkey,
+    /// Base deploy fee in `fee_mint` units
+    pub deploy_fee_token: u64,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Share of the lamport deploy fee refunded when a pool is cancelled
+    pub cancel_refund_bps: u16,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 2],
+}
+
+pub const FEE_SCHEDULE_SIZE: usize = 8 + FeeSchedule::INIT_SPACE;
+
+// Fee discounts of a partner creator at [CREATOR_TIER_SEED, platform, creator].
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorTier {
+    pub platform: Pubkey,
+    pub creator: Pubkey,
+    pub deploy_fee_discount_bps: u16,
+    /// Applied to the performance fee of the creator's pools created afterwards
+    pub performance_fee_discount_bps: u16,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES],
+}
+
+pub const CREATOR_TIER_SIZE: usize = 8 + CreatorTier::INIT_SPACE;
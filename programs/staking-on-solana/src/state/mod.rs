@@ -2,10 +2,16 @@ pub mod platform_info;
 pub mod pool_config;
 pub mod pool_state;
 pub mod user_info;
+pub mod escrow;
+pub mod registry;
+pub mod fee_schedule;
 pub mod constants;
 
 pub use platform_info::*;
 pub use pool_config::*;
 pub use pool_state::*;
 pub use user_info::*;
+pub use escrow::*;
+pub use registry::*;
+pub use fee_schedule::*;
 pub use constants::*;
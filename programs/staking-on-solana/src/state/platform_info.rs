@@ -1,10 +1,21 @@
 use anchor_lang::prelude::*;
 
+use crate::state::*;
+
 #[account]
+#[derive(InitSpace)]
 pub struct PlatformInfo {
     pub deploy_fee: u64,
     pub performance_fee: u64,
     pub treasury: Pubkey,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Pools listed in the registry so far
+    pub pool_count: u64,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 8],
 }
 
-pub const PLATFORM_INFO_SIZE: usize = 8 + 8 + 8 + 32;
+pub const PLATFORM_INFO_SIZE: usize = 8 + PlatformInfo::INIT_SPACE;
+// Deployed layout before accounts were versioned
+pub const PLATFORM_INFO_V1_SIZE: usize = 8 + 8 + 8 + 32;
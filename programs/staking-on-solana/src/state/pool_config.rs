@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::state::*;
+
 #[account]
+#[derive(InitSpace)]
 pub struct PoolConfig {
     pub owner: Pubkey,
     pub duration: u16,
@@ -16,6 +19,47 @@ pub struct PoolConfig {
     pub pool_stake_token_vault: Pubkey,
     pub pool_reward_token_vault: Pubkey,
     pub state_addr: Pubkey,
+    pub use_stable_swap: bool,
+    pub farm_program: Pubkey,
+    pub farm_pool: Pubkey,
+    pub pool_farm_reward_a_vault: Pubkey,
+    pub pool_farm_reward_b_vault: Pubkey,
+    pub time_based: bool,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub reward_per_second: u64,
+    pub stake_for_disabled: bool,
+    pub position_nft: bool,
+    /// Points claimed rewards vest over, 0 pays them out right away
+    pub vesting_period: u64,
+    /// Hand rewards forfeited by early leavers to the remaining stakers instead of the pool budget
+    pub redistribute_forfeits: bool,
+    /// Weigh stakes by the stakers' vote-escrow balance of the reward token
+    pub boost_enabled: bool,
+    /// Pool positions can migrate to once this one ended, the default key for none
+    pub successor_pool: Pubkey,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Position of the pool in the platform registry
+    pub registry_index: u64,
+    /// Creator tier discount on the platform performance fee, fixed at creation
+    pub performance_fee_discount_bps: u16,
+    /// Deploy fee paid in lamports, zero when paid in the fee token
+    pub deploy_fee_paid: u64,
+    /// Reward season the pool is running, zero for the campaign it was created with
+    pub season: u32,
+    /// Unstake penalty right after depositing, replaces the unstake fee while non-zero
+    pub early_exit_penalty_bps: u16,
+    /// Days the early-exit penalty decays to zero over
+    pub early_exit_penalty_days: u16,
+    /// Hand early-exit penalties to the remaining stakers instead of the creator
+    pub redistribute_penalties: bool,
+    /// EscrowTotals boosted pools weigh stakes by, set by set_boost
+    pub escrow_totals: Pubkey,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 59],
 }
 
-pub const POOL_CONFIG_SIZE: usize = 8 + 32 + 2 + 8 + 8 + 8 + 2 + 2 + 32 + 32 + 1 + 1 + 32 + 32 + 32;
+pub const POOL_CONFIG_SIZE: usize = 8 + PoolConfig::INIT_SPACE;
+// Deployed layout before accounts were versioned
+pub const POOL_CONFIG_V1_SIZE: usize = 8 + 32 + 2 + 8 + 8 + 8 + 2 + 2 + 32 + 32 + 1 + 1 + 32 + 32 + 32;
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::state::*;
+
 #[account]
+#[derive(InitSpace)]
 pub struct PoolState {
     pub total_staked: u64,
     pub last_reward_slot: u64,
@@ -9,6 +12,25 @@ pub struct PoolState {
     pub should_total_paid: u64,
     pub paid_rewards: u64,
     pub total_earned: u64,
+    pub farm_staked: u64,
+    pub acc_farm_reward_a_per_share: u128,
+    pub acc_farm_reward_b_per_share: u128,
+    pub last_reward_time: u64,
+    /// Sequence number of the last event emitted for this pool
+    pub event_seq: u64,
+    /// Claimed rewards still held for vesting positions
+    pub total_vesting: u64,
+    /// Sum of the stakers' boosted balances rewards are shared by
+    pub total_working: u64,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Farm rewards harvested while nothing was staked, shared with the next harvest
+    pub undistributed_farm_reward_a: u64,
+    pub undistributed_farm_reward_b: u64,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 16],
 }
 
-pub const POOL_STATE_SIZE: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+pub const POOL_STATE_SIZE: usize = 8 + PoolState::INIT_SPACE;
+// Deployed layout before accounts were versioned
+pub const POOL_STATE_V1_SIZE: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RegistryEntry {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    /// POOL_STATUS_ACTIVE or POOL_STATUS_CLOSED
+    pub status: u8,
+}
+
+// Page of the platform's pool registry at [REGISTRY_SEED, platform, page].
+// Pool number n lives on page n / REGISTRY_PAGE_LEN.
+#[account]
+#[derive(InitSpace)]
+pub struct RegistryPage {
+    pub platform: Pubkey,
+    pub page: u32,
+    #[max_len(REGISTRY_PAGE_LEN)]
+    pub entries: Vec<RegistryEntry>,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES],
+}
+
+pub const REGISTRY_PAGE_SIZE: usize = 8 + RegistryPage::INIT_SPACE;
+
+// Number of pools listed under one creator or stake mint, at
+// [REGISTRY_SEED, kind, key] with kind REGISTRY_CREATOR_SEED or REGISTRY_MINT_SEED.
+#[account]
+#[derive(InitSpace)]
+pub struct RegistryIndex {
+    pub platform: Pubkey,
+    pub key: Pubkey,
+    pub count: u64,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES],
+}
+
+pub const REGISTRY_INDEX_SIZE: usize = 8 + RegistryIndex::INIT_SPACE;
+
+// Page of a creator or stake mint listing at [REGISTRY_SEED, kind, key, page],
+// holding the pools' positions in the platform registry.
+#[account]
+#[derive(InitSpace)]
+pub struct RegistryIndexPage {
+    pub key: Pubkey,
+    pub page: u32,
+    #[max_len(REGISTRY_PAGE_LEN)]
+    pub positions: Vec<u64>,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES],
+}
+
+pub const REGISTRY_INDEX_PAGE_SIZE: usize = 8 + RegistryIndexPage::INIT_SPACE;
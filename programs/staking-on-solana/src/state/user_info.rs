@@ -1,10 +1,35 @@
 use anchor_lang::prelude::*;
 
+use crate::state::*;
+
 #[account]
+#[derive(InitSpace)]
 pub struct UserInfo {
     pub staked_amount: u64,
     pub reward_debt: u64,
     pub deposit_slot: u64,
+    pub farm_reward_a_debt: u64,
+    pub farm_reward_b_debt: u64,
+    pub farm_reward_a_owed: u64,
+    pub farm_reward_b_owed: u64,
+    /// Wallet claim_reward pays out to, the default key pays the owner
+    pub reward_recipient: Pubkey,
+    /// Wallet allowed to claim and compound for the owner, the default key allows none
+    pub claim_delegate: Pubkey,
+    /// Claimed rewards still vesting between `vesting_start` and `vesting_end`
+    pub vesting_locked: u64,
+    /// Vested rewards ready for withdraw_vested
+    pub vesting_claimable: u64,
+    pub vesting_start: u64,
+    pub vesting_end: u64,
+    /// Boosted balance the user's share of rewards is computed on
+    pub working_amount: u64,
+    /// Layout version, see CURRENT_ACCOUNT_VERSION
+    pub version: u8,
+    /// Room for new fields, carved out as the version is bumped
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES],
 }
 
-pub const USER_INFO_SIZE: usize = 8 + 8 + 8 + 8;
+pub const USER_INFO_SIZE: usize = 8 + UserInfo::INIT_SPACE;
+// Deployed layout before accounts were versioned
+pub const USER_INFO_V1_SIZE: usize = 8 + 8 + 8 + 8;
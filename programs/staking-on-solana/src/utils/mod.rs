@@ -1,28 +1,41 @@
 use anchor_lang::prelude::*;
-// use anchor_spl::token::{ self, Transfer };
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program;
+use anchor_spl::token::{
+    self,
+    spl_token,
+    CloseAccount,
+    InitializeAccount3,
+    SyncNative,
+    TokenAccount,
+    Transfer,
+};
 // use anchor_spl::token_interface::TokenAccount;
 
 use crate::state::*;
+use crate::error::*;
+use crate::events::*;
 
 // Update reward variables of the given pool to be up-to-date.
 pub fn update_pool<'info>(
     pool_config: &Account<'info, PoolConfig>,
     pool_state: &mut Account<'info, PoolState>
 ) -> Result<()> {
-    let clock = Clock::get()?;
+    let current = current_point(pool_config)?;
+    let last_reward = last_reward_point(pool_config, pool_state);
     // msg!("@@update pool start");
     // msg!("@@current slot {}", clock.slot);
-    if clock.slot <= pool_state.last_reward_slot || pool_state.last_reward_slot == 0 {
+    if current <= last_reward || last_reward == 0 {
         return Ok(());
     }
     // msg!("@@pool_state.total_staked {}", pool_state.total_staked);
-    if pool_state.total_staked == 0 {
-        pool_state.last_reward_slot = clock.slot;
+    if pool_state.total_working == 0 {
+        set_last_reward_point(pool_config, pool_state, current);
         return Ok(());
     }
 
-    let multiplier = get_multiplier(pool_state.last_reward_slot, clock.slot, pool_config.end_slot);
-    let reward = multiplier * pool_config.reward_per_slot;
+    let multiplier = get_multiplier(last_reward, current, end_point(pool_config));
+    let reward = multiplier * reward_rate(pool_config);
     let precision_factor = get_precision_factor(pool_config);
 
     // msg!("@@multiplier {}", multiplier);
@@ -30,9 +43,9 @@ pub fn update_pool<'info>(
     // msg!("@@reward {}", reward);
     // msg!("@@precision_factor {}", precision_factor);
 
-    pool_state.acc_token_per_share += (reward * precision_factor) / pool_state.total_staked;
+    pool_state.acc_token_per_share += (reward * precision_factor) / pool_state.total_working;
 
-    pool_state.last_reward_slot = clock.slot;
+    set_last_reward_point(pool_config, pool_state, current);
     pool_state.should_total_paid += reward;
 
     // msg!("@@pool_state.acc_token_per_share {}", pool_state.acc_token_per_share);
@@ -42,6 +55,886 @@ pub fn update_pool<'info>(
     Ok(())
 }
 
+// Credit farm rewards harvested by the pool to all stakers pro-rata. With nothing
+// staked they are held back for the stakers of the next harvest.
+pub fn distribute_farm_rewards(pool_state: &mut PoolState, harvested_a: u64, harvested_b: u64) {
+    let reward_a = pool_state.undistributed_farm_reward_a + harvested_a;
+    let reward_b = pool_state.undistributed_farm_reward_b + harvested_b;
+    if pool_state.total_staked == 0 {
+        pool_state.undistributed_farm_reward_a = reward_a;
+        pool_state.undistributed_farm_reward_b = reward_b;
+        return;
+    }
+    pool_state.undistributed_farm_reward_a = 0;
+    pool_state.undistributed_farm_reward_b = 0;
+    let total_staked = pool_state.total_staked as u128;
+    pool_state.acc_farm_reward_a_per_share +=
+        ((reward_a as u128) * FARM_REWARD_PRECISION) / total_staked;
+    pool_state.acc_farm_reward_b_per_share +=
+        ((reward_b as u128) * FARM_REWARD_PRECISION) / total_staked;
+}
+
+// Pay out the farm rewards owed to a position. Returns the amounts paid.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_farm_rewards<'info>(
+    pool_state: &mut PoolState,
+    user_info: &mut UserInfo,
+    pool_farm_reward_a_vault: AccountInfo<'info>,
+    pool_farm_reward_b_vault: AccountInfo<'info>,
+    user_farm_reward_a_vault: AccountInfo<'info>,
+    user_farm_reward_b_vault: AccountInfo<'info>,
+    admin: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<(u64, u64)> {
+    settle_farm_rewards(pool_state, user_info);
+
+    let owed_a = user_info.farm_reward_a_owed;
+    let owed_b = user_info.farm_reward_b_owed;
+
+    if owed_a > 0 {
+        transfer_tokens(
+            pool_farm_reward_a_vault,
+            user_farm_reward_a_vault,
+            admin.clone(),
+            owed_a,
+            token_program.clone()
+        )?;
+        user_info.farm_reward_a_owed = 0;
+    }
+
+    if owed_b > 0 {
+        transfer_tokens(
+            pool_farm_reward_b_vault,
+            user_farm_reward_b_vault,
+            admin,
+            owed_b,
+            token_program
+        )?;
+        user_info.farm_reward_b_owed = 0;
+    }
+    Ok((owed_a, owed_b))
+}
+
+// Withdraw from the farm what the pool vault lacks to pay out `amount`, harvesting the
+// farm rewards on the way. Must run before the leaver's farm rewards are settled.
+pub fn withdraw_farm_shortfall<'info>(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut PoolState,
+    amount: u64,
+    pool_stake_token_vault: &mut Account<'info, TokenAccount>,
+    farm_accounts: &'info [AccountInfo<'info>],
+    admin: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<()> {
+    let shortfall = amount.saturating_sub(pool_stake_token_vault.amount);
+    if shortfall == 0 || pool_state.farm_staked == 0 {
+        return Ok(());
+    }
+    require!(farm_accounts.len() == FARM_WITHDRAW_ACCOUNTS, BrewStakingError::FarmAccountsMissing);
+    require_keys_eq!(farm_accounts[0].key(), pool_config.farm_program, BrewStakingError::FarmNotEnabled);
+    require_keys_eq!(farm_accounts[1].key(), pool_config.farm_pool, BrewStakingError::FarmNotEnabled);
+    require_keys_eq!(
+        farm_accounts[7].key(),
+        pool_config.pool_farm_reward_a_vault,
+        BrewStakingError::FarmNotEnabled
+    );
+    require_keys_eq!(
+        farm_accounts[8].key(),
+        pool_config.pool_farm_reward_b_vault,
+        BrewStakingError::FarmNotEnabled
+    );
+
+    let reward_a_before = Account::<TokenAccount>::try_from(&farm_accounts[7])?.amount;
+    let reward_b_before = Account::<TokenAccount>::try_from(&farm_accounts[8])?.amount;
+
+    let withdrawn = shortfall.min(pool_state.farm_staked);
+    let withdraw_accounts = amm_anchor::FarmWithdraw {
+        stake_pool: farm_accounts[1].clone(),
+        pool_authority: farm_accounts[2].clone(),
+        staker_info: farm_accounts[3].clone(),
+        staker_owner: admin,
+        dest_lp_token: pool_stake_token_vault.to_account_info(),
+        vault_lp_token: farm_accounts[4].clone(),
+        dest_reward_token_a: farm_accounts[7].clone(),
+        vault_reward_token_a: farm_accounts[5].clone(),
+        dest_reward_token_b: farm_accounts[8].clone(),
+        vault_reward_token_b: farm_accounts[6].clone(),
+        clock: Sysvar::from_account_info(&farm_accounts[9])?,
+        spl_token_program: token_program,
+    };
+    let cpi_ctx = CpiContext::new(farm_accounts[0].clone(), withdraw_accounts);
+    amm_anchor::farm_withdraw_v2(cpi_ctx, withdrawn)?;
+    pool_stake_token_vault.reload()?;
+
+    let harvested_a = Account::<TokenAccount>::try_from(&farm_accounts[7])?.amount - reward_a_before;
+    let harvested_b = Account::<TokenAccount>::try_from(&farm_accounts[8])?.amount - reward_b_before;
+    distribute_farm_rewards(pool_state, harvested_a, harvested_b);
+    pool_state.farm_staked -= withdrawn;
+
+    emit!(FarmWithdrawn {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot: Clock::get()?.slot,
+        amount: withdrawn,
+        harvested_a,
+        harvested_b,
+        farm_staked: pool_state.farm_staked,
+    });
+    Ok(())
+}
+
+// Move the farm rewards earned by the current stake into the user's owed balance.
+// Must run before `staked_amount` changes.
+pub fn settle_farm_rewards(pool_state: &PoolState, user_info: &mut UserInfo) {
+    let staked = user_info.staked_amount as u128;
+    let accrued_a = (staked * pool_state.acc_farm_reward_a_per_share) / FARM_REWARD_PRECISION;
+    let accrued_b = (staked * pool_state.acc_farm_reward_b_per_share) / FARM_REWARD_PRECISION;
+    user_info.farm_reward_a_owed += (accrued_a as u64).saturating_sub(user_info.farm_reward_a_debt);
+    user_info.farm_reward_b_owed += (accrued_b as u64).saturating_sub(user_info.farm_reward_b_debt);
+    sync_farm_reward_debts(pool_state, user_info);
+}
+
+// Reset the farm reward debts to the user's current stake.
+// Must run after `staked_amount` changes.
+pub fn sync_farm_reward_debts(pool_state: &PoolState, user_info: &mut UserInfo) {
+    let staked = user_info.staked_amount as u128;
+    user_info.farm_reward_a_debt = ((staked * pool_state.acc_farm_reward_a_per_share) /
+        FARM_REWARD_PRECISION) as u64;
+    user_info.farm_reward_b_debt = ((staked * pool_state.acc_farm_reward_b_per_share) /
+        FARM_REWARD_PRECISION) as u64;
+}
+
+// Whether `signer` may claim or compound the position of `owner`.
+pub fn can_claim_for(user_info: &UserInfo, owner: Pubkey, signer: Pubkey) -> bool {
+    signer == owner ||
+        (user_info.claim_delegate != Pubkey::default() && signer == user_info.claim_delegate)
+}
+
+// Wallet that receives the claimed rewards of `owner`'s position.
+pub fn reward_payout_owner(user_info: &UserInfo, owner: Pubkey) -> Pubkey {
+    if user_info.reward_recipient != Pubkey::default() {
+        user_info.reward_recipient
+    } else {
+        owner
+    }
+}
+
+// Move the part of the user's locked rewards vested by `now` to claimable.
+pub fn checkpoint_vesting(user_info: &mut UserInfo, now: u64) {
+    if user_info.vesting_locked == 0 || now <= user_info.vesting_start {
+        return;
+    }
+    let vested = if now >= user_info.vesting_end {
+        user_info.vesting_locked
+    } else {
+        (((user_info.vesting_locked as u128) * ((now - user_info.vesting_start) as u128)) /
+            ((user_info.vesting_end - user_info.vesting_start) as u128)) as u64
+    };
+    user_info.vesting_locked -= vested;
+    user_info.vesting_claimable += vested;
+    user_info.vesting_start = now;
+}
+
+// Lock claimed rewards in the user's vesting ledger instead of paying them out.
+// Anything still locked vests again over a full period from now.
+// Callers keep accounting the amount as paid from the pool's reward budget.
+pub fn vest_rewards(
+    pool_config: &PoolConfig,
+    pool_state: &mut PoolState,
+    user_info: &mut UserInfo,
+    amount: u64
+) -> Result<()> {
+    let now = current_point(pool_config)?;
+    checkpoint_vesting(user_info, now);
+    user_info.vesting_locked += amount;
+    user_info.vesting_start = now;
+    user_info.vesting_end = now + pool_config.vesting_period;
+    pool_state.total_vesting += amount;
+    Ok(())
+}
+
+// Forfeit the share of the user's locked rewards matching the share of stake being
+// withdrawn, either back to the pool budget or to the other stakers.
+// Must run after the user's stake and working balance are reduced, and the user's
+// reward debt reset afterwards. Returns the forfeited amount and whether it was
+// redistributed.
+pub fn forfeit_unvested(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut PoolState,
+    user_info: &mut UserInfo,
+    withdrawn: u64,
+    staked_before: u64
+) -> Result<(u64, bool)> {
+    if user_info.vesting_locked == 0 || staked_before == 0 {
+        return Ok((0, false));
+    }
+    checkpoint_vesting(user_info, current_point(pool_config)?);
+    let forfeited = (((user_info.vesting_locked as u128) * (withdrawn as u128)) /
+        (staked_before as u128)) as u64;
+    if forfeited == 0 {
+        return Ok((0, false));
+    }
+    user_info.vesting_locked -= forfeited;
+    pool_state.total_vesting -= forfeited;
+    pool_state.reward_amount += forfeited;
+
+    let others_staked = pool_state.total_working - user_info.working_amount;
+    if pool_config.redistribute_forfeits && others_staked > 0 {
+        // Owed to the other stakers from now on
+        pool_state.should_total_paid += forfeited;
+        pool_state.acc_token_per_share +=
+            (forfeited * get_precision_factor(pool_config)) / others_staked;
+        return Ok((forfeited, true));
+    }
+
+    // Back into the budget, refundable to the creator on stop
+    pool_state.paid_rewards -= forfeited;
+    Ok((forfeited, false))
+}
+
+// Deposit slot after adding `added` to the position, the stake-weighted average of
+// the old deposit and now, so topping up can't reset or dodge the early-exit penalty.
+// Stake past the decay period weighs in as fully decayed, however old it is, so a
+// small top-up on an old position doesn't leave the new stake penalty-free.
+pub fn weighted_deposit_slot(
+    pool_config: &PoolConfig,
+    user_info: &UserInfo,
+    added: u64,
+    slot: u64
+) -> u64 {
+    let staked = user_info.staked_amount as u128;
+    if staked == 0 || user_info.deposit_slot == 0 {
+        return slot;
+    }
+    let mut deposit_slot = user_info.deposit_slot;
+    if pool_config.early_exit_penalty_bps > 0 {
+        let decay = (pool_config.early_exit_penalty_days as u64) * SLOTS_PER_DAY;
+        deposit_slot = deposit_slot.max(slot.saturating_sub(decay));
+    }
+    let total = staked + (added as u128);
+    (((staked * (deposit_slot as u128)) + (added as u128) * (slot as u128)) / total) as u64
+}
+
+// Fee on `amount` leaving the pool. With an early-exit penalty it starts at the
+// penalty and decays linearly to zero over the penalty days since the deposit,
+// otherwise it's the flat unstake fee.
+pub fn unstake_fee_of(pool_config: &PoolConfig, user_info: &UserInfo, amount: u64, slot: u64) -> u64 {
+    if pool_config.early_exit_penalty_bps == 0 {
+        return (amount * (pool_config.unstake_fee as u64)) / PERCENT_PRECISION;
+    }
+    let decay = (pool_config.early_exit_penalty_days as u64) * SLOTS_PER_DAY;
+    let elapsed = slot.saturating_sub(user_info.deposit_slot);
+    if elapsed >= decay {
+        return 0;
+    }
+    (((amount as u128) * (pool_config.early_exit_penalty_bps as u128) * ((decay - elapsed) as u128)) /
+        ((PERCENT_PRECISION as u128) * (decay as u128))) as u64
+}
+
+// Pay an unstake fee out of the pool's stake vault. Early-exit penalties go to the
+// other stakers when the pool redistributes them and anyone else is staked, everything
+// else to the creator. Must run before the leaver's reward debt is reset, so the leaver
+// gets no share. Returns whether the fee was redistributed.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_unstake_fee<'info>(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut PoolState,
+    user_info: &UserInfo,
+    fee: u64,
+    pool_stake_token_vault: AccountInfo<'info>,
+    pool_reward_token_vault: AccountInfo<'info>,
+    creator_stake_token_vault: AccountInfo<'info>,
+    admin: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<bool> {
+    let others_staked = pool_state.total_working - user_info.working_amount;
+    let redistribute =
+        fee > 0 &&
+        pool_config.early_exit_penalty_bps > 0 &&
+        pool_config.redistribute_penalties &&
+        others_staked > 0;
+    if !redistribute {
+        transfer_tokens(
+            pool_stake_token_vault,
+            creator_stake_token_vault,
+            admin,
+            fee,
+            token_program
+        )?;
+        return Ok(false);
+    }
+
+    // Stake and reward mint are the same, the penalty joins the reward budget
+    if pool_stake_token_vault.key() != pool_reward_token_vault.key() {
+        transfer_tokens(pool_stake_token_vault, pool_reward_token_vault, admin, fee, token_program)?;
+    }
+    pool_state.reward_amount += fee;
+    pool_state.should_total_paid += fee;
+    pool_state.acc_token_per_share += (fee * get_precision_factor(pool_config)) / others_staked;
+    Ok(true)
+}
+
+// Settle what the staker earned so far before their stake changes. Vesting pools lock
+// it up, otherwise the returned amount is owed and the caller pays it out. Claims
+// report the performance fee they charged, stake changes report theirs on the
+// Deposit or Withdraw event.
+pub fn take_pending_reward(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut Account<PoolState>,
+    user_info: &mut UserInfo,
+    claimer: Pubkey,
+    performance_fee: u64,
+    slot: u64
+) -> Result<u64> {
+    let pending =
+        (user_info.working_amount * pool_state.acc_token_per_share) /
+            get_precision_factor(pool_config) -
+        user_info.reward_debt;
+    if pending == 0 {
+        return Ok(0);
+    }
+    require!(
+        available_reward_tokens(pool_config, pool_state) >= pending,
+        BrewStakingError::InsufficientReward
+    );
+
+    let payout = if pool_config.vesting_period > 0 {
+        vest_rewards(pool_config, pool_state, user_info, pending)?;
+        0
+    } else {
+        pending
+    };
+
+    pool_state.reward_amount -= pending;
+    pool_state.total_earned = pool_state.total_earned.saturating_sub(pending);
+    pool_state.paid_rewards += pending;
+
+    emit!(RewardClaim {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot,
+        claimer,
+        amount: pending,
+        performance_fee,
+        reward_amount: pool_state.reward_amount,
+        paid_rewards: pool_state.paid_rewards,
+    });
+    Ok(payout)
+}
+
+// Book a deposit already in the pool's stake vault: pay the stake fee to the creator
+// and add the rest to the staker's stake and working balance. Returns the stake fee.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_stake<'info>(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut Account<PoolState>,
+    user_info: &mut UserInfo,
+    staker: Pubkey,
+    stake_amount: u64,
+    performance_fee: u64,
+    ve_balance: u128,
+    ve_total: u128,
+    slot: u64,
+    pool_stake_token_vault: AccountInfo<'info>,
+    creator_stake_token_vault: AccountInfo<'info>,
+    admin: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<u64> {
+    let stake_fee = (stake_amount * (pool_config.stake_fee as u64)) / PERCENT_PRECISION;
+    transfer_tokens(
+        pool_stake_token_vault,
+        creator_stake_token_vault,
+        admin,
+        stake_fee,
+        token_program
+    )?;
+
+    credit_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        staker,
+        stake_amount - stake_fee,
+        stake_fee,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot
+    );
+    Ok(stake_fee)
+}
+
+// Add `amount` to the staker's stake and working balance once the fees are settled.
+#[allow(clippy::too_many_arguments)]
+pub fn credit_stake(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut Account<PoolState>,
+    user_info: &mut UserInfo,
+    staker: Pubkey,
+    amount: u64,
+    stake_fee: u64,
+    performance_fee: u64,
+    ve_balance: u128,
+    ve_total: u128,
+    slot: u64
+) {
+    user_info.deposit_slot = weighted_deposit_slot(pool_config, user_info, amount, slot);
+    user_info.staked_amount += amount;
+    pool_state.total_staked += amount;
+    user_info.version = CURRENT_ACCOUNT_VERSION;
+    update_working_amount(pool_config, pool_state, user_info, ve_balance, ve_total);
+    user_info.reward_debt =
+        (user_info.working_amount * pool_state.acc_token_per_share) /
+        get_precision_factor(pool_config);
+    sync_farm_reward_debts(pool_state, user_info);
+
+    emit!(Deposit {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot,
+        staker,
+        amount,
+        stake_fee,
+        performance_fee,
+        user_staked: user_info.staked_amount,
+        total_staked: pool_state.total_staked,
+    });
+}
+
+// Take `amount` off the staker's stake and working balance once the fees are settled.
+#[allow(clippy::too_many_arguments)]
+pub fn debit_stake(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut Account<PoolState>,
+    user_info: &mut UserInfo,
+    staker: Pubkey,
+    amount: u64,
+    unstake_fee: u64,
+    performance_fee: u64,
+    ve_balance: u128,
+    ve_total: u128,
+    slot: u64
+) {
+    user_info.staked_amount -= amount;
+    pool_state.total_staked -= amount;
+    update_working_amount(pool_config, pool_state, user_info, ve_balance, ve_total);
+    user_info.reward_debt =
+        (user_info.working_amount * pool_state.acc_token_per_share) /
+        get_precision_factor(pool_config);
+    sync_farm_reward_debts(pool_state, user_info);
+
+    emit!(Withdraw {
+        version: EVENT_VERSION,
+        pool: pool_config.key(),
+        seq: next_event_seq(pool_state),
+        slot,
+        staker,
+        amount,
+        unstake_fee,
+        performance_fee,
+        user_staked: user_info.staked_amount,
+        total_staked: pool_state.total_staked,
+    });
+}
+
+// Book `amount` leaving the stake: pay the unstake fee or early-exit penalty, reduce
+// the stake and working balance and forfeit the matching share of vesting rewards.
+// Returns the fee, the caller pays out `amount` less the fee from the stake vault.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_unstake<'info>(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut Account<PoolState>,
+    user_info: &mut UserInfo,
+    staker: Pubkey,
+    amount: u64,
+    performance_fee: u64,
+    ve_balance: u128,
+    ve_total: u128,
+    slot: u64,
+    pool_stake_token_vault: AccountInfo<'info>,
+    pool_reward_token_vault: AccountInfo<'info>,
+    creator_stake_token_vault: AccountInfo<'info>,
+    admin: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<u64> {
+    let pool_key = pool_config.key();
+    let precision_factor = get_precision_factor(pool_config);
+
+    let unstake_fee = unstake_fee_of(pool_config, user_info, amount, slot);
+    let penalty_redistributed = pay_unstake_fee(
+        pool_config,
+        pool_state,
+        user_info,
+        unstake_fee,
+        pool_stake_token_vault,
+        pool_reward_token_vault,
+        creator_stake_token_vault,
+        admin,
+        token_program
+    )?;
+    if penalty_redistributed {
+        emit!(PenaltyRedistributed {
+            version: EVENT_VERSION,
+            pool: pool_key,
+            seq: next_event_seq(pool_state),
+            slot,
+            user: staker,
+            amount: unstake_fee,
+            acc_token_per_share: pool_state.acc_token_per_share,
+        });
+    }
+
+    debit_stake(
+        pool_config,
+        pool_state,
+        user_info,
+        staker,
+        amount,
+        unstake_fee,
+        performance_fee,
+        ve_balance,
+        ve_total,
+        slot
+    );
+
+    // Leaving early forfeits the matching share of the still vesting rewards
+    let staked_before = user_info.staked_amount + amount;
+    let (forfeited, redistributed) = forfeit_unvested(
+        pool_config,
+        pool_state,
+        user_info,
+        amount,
+        staked_before
+    )?;
+    if forfeited > 0 {
+        user_info.reward_debt =
+            (user_info.working_amount * pool_state.acc_token_per_share) / precision_factor;
+
+        emit!(RewardsForfeited {
+            version: EVENT_VERSION,
+            pool: pool_key,
+            seq: next_event_seq(pool_state),
+            slot,
+            user: staker,
+            amount: forfeited,
+            redistributed,
+            total_vesting: pool_state.total_vesting,
+        });
+    }
+    Ok(unstake_fee)
+}
+
+// Vote-escrow weight of a lock at `now`.
+pub fn escrow_ve_balance(escrow: &Escrow, now: u64) -> u128 {
+    if now >= escrow.lock_end {
+        return 0;
+    }
+    ((escrow.amount as u128) * ((escrow.lock_end - now) as u128)) /
+        (MAX_ESCROW_LOCK_SECONDS as u128)
+}
+
+// Week boundaries passed after `from` up to `to`, as weeks since the epoch. Every
+// lock ends within ESCROW_SCHEDULE_WEEKS of the last checkpoint, later weeks expire nothing.
+fn escrow_weeks_between(from: u64, to: u64) -> std::ops::RangeInclusive<u64> {
+    let first = from / ESCROW_WEEK_SECONDS + 1;
+    let last = (to / ESCROW_WEEK_SECONDS).min(first + (ESCROW_SCHEDULE_WEEKS as u64) - 1);
+    first..=last
+}
+
+fn escrow_schedule_index(week: u64) -> usize {
+    (week % (ESCROW_SCHEDULE_WEEKS as u64)) as usize
+}
+
+// Running amount and amount * lock_end sums of the locks still running at `now`.
+pub fn active_escrow_sums(escrow_totals: &EscrowTotals, now: u64) -> (u64, u128) {
+    let mut active_locked = escrow_totals.active_locked;
+    let mut locked_end_sum = escrow_totals.locked_end_sum;
+    for week in escrow_weeks_between(escrow_totals.checkpoint_time, now) {
+        let expiring = escrow_totals.expiring[escrow_schedule_index(week)];
+        active_locked -= expiring;
+        locked_end_sum -= (expiring as u128) * ((week * ESCROW_WEEK_SECONDS) as u128);
+    }
+    (active_locked, locked_end_sum)
+}
+
+// Drop the locks that expired by `now` from the running sums.
+pub fn checkpoint_escrow_totals(escrow_totals: &mut EscrowTotals, now: u64) {
+    let (active_locked, locked_end_sum) = active_escrow_sums(escrow_totals, now);
+    for week in escrow_weeks_between(escrow_totals.checkpoint_time, now) {
+        escrow_totals.expiring[escrow_schedule_index(week)] = 0;
+    }
+    escrow_totals.active_locked = active_locked;
+    escrow_totals.locked_end_sum = locked_end_sum;
+    escrow_totals.checkpoint_time = now;
+}
+
+// Add a running lock to the sums, or take it out again with `add` false.
+// The totals must be checkpointed to a time before `lock_end`.
+pub fn schedule_escrow_lock(
+    escrow_totals: &mut EscrowTotals,
+    amount: u64,
+    lock_end: u64,
+    add: bool
+) {
+    let index = escrow_schedule_index(lock_end / ESCROW_WEEK_SECONDS);
+    let weighted = (amount as u128) * (lock_end as u128);
+    if add {
+        escrow_totals.active_locked += amount;
+        escrow_totals.locked_end_sum += weighted;
+        escrow_totals.expiring[index] += amount;
+    } else {
+        escrow_totals.active_locked -= amount;
+        escrow_totals.locked_end_sum -= weighted;
+        escrow_totals.expiring[index] -= amount;
+    }
+}
+
+// Vote-escrow weight of all locks still running at `now`.
+pub fn escrow_ve_total(escrow_totals: &EscrowTotals, now: u64) -> u128 {
+    let (active_locked, locked_end_sum) = active_escrow_sums(escrow_totals, now);
+    (locked_end_sum - (now as u128) * (active_locked as u128)) / (MAX_ESCROW_LOCK_SECONDS as u128)
+}
+
+// Vote-escrow weight of `owner` and of everyone in the pool's escrow totals.
+// Boosted pools need both accounts so no caller can drop a staker to the base
+// weight by leaving them out. An escrow that doesn't exist, or is counted in
+// other totals, weighs nothing.
+pub fn ve_balances(
+    pool_config: &PoolConfig,
+    escrow: Option<&AccountInfo>,
+    escrow_totals: Option<&Account<EscrowTotals>>,
+    owner: Pubkey
+) -> Result<(u128, u128)> {
+    if !pool_config.boost_enabled {
+        return Ok((0, 0));
+    }
+    let (Some(escrow), Some(escrow_totals)) = (escrow, escrow_totals) else {
+        return err!(BrewStakingError::EscrowAccountsMissing);
+    };
+    require_keys_eq!(escrow_totals.key(), pool_config.escrow_totals, BrewStakingError::WrongEscrow);
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[ESCROW_SEED, pool_config.reward_mint.as_ref(), owner.as_ref()],
+        &crate::ID
+    );
+    require_keys_eq!(escrow.key(), escrow_key, BrewStakingError::WrongEscrow);
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let ve_total = escrow_ve_total(escrow_totals, now);
+    if escrow.data_is_empty() {
+        return Ok((0, ve_total));
+    }
+    require_keys_eq!(*escrow.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let escrow = Escrow::try_deserialize(&mut &escrow.try_borrow_data()?[..])?;
+    if escrow.totals != escrow_totals.key() {
+        return Ok((0, ve_total));
+    }
+    Ok((escrow_ve_balance(&escrow, now), ve_total))
+}
+
+// Recompute the user's boosted balance with the Curve formula
+// min(stake, 0.4 * stake + 0.6 * total_staked * ve / ve_total).
+// Must run after `staked_amount` and `total_staked` change, before the reward debt is reset.
+pub fn update_working_amount(
+    pool_config: &PoolConfig,
+    pool_state: &mut PoolState,
+    user_info: &mut UserInfo,
+    ve_balance: u128,
+    ve_total: u128
+) {
+    let staked = user_info.staked_amount as u128;
+    let working = if pool_config.boost_enabled {
+        let mut boosted = (staked * (BOOST_BASE_BPS as u128)) / (PERCENT_PRECISION as u128);
+        if ve_total > 0 {
+            boosted +=
+                ((pool_state.total_staked as u128) *
+                    ve_balance *
+                    ((PERCENT_PRECISION - BOOST_BASE_BPS) as u128)) /
+                (ve_total * (PERCENT_PRECISION as u128));
+        }
+        boosted.min(staked) as u64
+    } else {
+        staked as u64
+    };
+
+    pool_state.total_working = pool_state.total_working - user_info.working_amount + working;
+    user_info.working_amount = working;
+}
+
+// Hand out the next per-pool event sequence number.
+pub fn next_event_seq(pool_state: &mut PoolState) -> u64 {
+    pool_state.event_seq += 1;
+    pool_state.event_seq
+}
+
+// Grow an account written with the unversioned v1 layout to the current one; the
+// payer tops up the rent. Fields added since come out zeroed, callers set the ones
+// that need another value and the version, then write the account back.
+// Returns false for accounts already migrated, which are left alone.
+pub fn migrate_account<'info, T: anchor_lang::Discriminator + Owner>(
+    account: &AccountInfo<'info>,
+    v1_size: usize,
+    new_size: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>
+) -> Result<bool> {
+    require_keys_eq!(*account.owner, T::owner(), ErrorCode::AccountOwnedByWrongProgram);
+    {
+        let data = account.try_borrow_data()?;
+        require!(data.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+        require!(data[..8] == T::DISCRIMINATOR, ErrorCode::AccountDiscriminatorMismatch);
+    }
+
+    let len = account.data_len();
+    if len == new_size {
+        return Ok(false);
+    }
+    require!(len == v1_size, BrewStakingError::InvalidAccountVersion);
+
+    let rent = Rent::get()?.minimum_balance(new_size).saturating_sub(account.lamports());
+    if rent > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        anchor_lang::system_program::transfer(cpi_ctx, rent)?;
+    }
+
+    account.realloc(new_size, true)?;
+
+    Ok(true)
+}
+
+// Write an account loaded by hand back into its data.
+pub fn write_account<T: AccountSerialize>(account: &AccountInfo, value: &T) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])
+}
+
+// Slot-based pools are scheduled in slots, time-based pools in unix timestamps.
+// The helpers below return the value in the unit the pool is scheduled in.
+pub fn current_point(pool_config: &PoolConfig) -> Result<u64> {
+    let clock = Clock::get()?;
+    if pool_config.time_based {
+        return Ok(clock.unix_timestamp as u64);
+    }
+    Ok(clock.slot)
+}
+
+pub fn start_point(pool_config: &PoolConfig) -> u64 {
+    if pool_config.time_based { pool_config.start_time } else { pool_config.start_slot }
+}
+
+pub fn end_point(pool_config: &PoolConfig) -> u64 {
+    if pool_config.time_based { pool_config.end_time } else { pool_config.end_slot }
+}
+
+pub fn reward_rate(pool_config: &PoolConfig) -> u64 {
+    if pool_config.time_based { pool_config.reward_per_second } else { pool_config.reward_per_slot }
+}
+
+pub fn points_per_day(pool_config: &PoolConfig) -> u64 {
+    if pool_config.time_based { SECONDS_PER_DAY } else { SLOTS_PER_DAY }
+}
+
+pub fn last_reward_point(pool_config: &PoolConfig, pool_state: &PoolState) -> u64 {
+    if pool_config.time_based { pool_state.last_reward_time } else { pool_state.last_reward_slot }
+}
+
+pub fn set_last_reward_point(pool_config: &PoolConfig, pool_state: &mut PoolState, point: u64) {
+    if pool_config.time_based {
+        pool_state.last_reward_time = point;
+    } else {
+        pool_state.last_reward_slot = point;
+    }
+}
+
+// Point the rewards start at: a zero `start_at` starts after a short delay, otherwise
+// the pool is scheduled for `start_at`.
+pub fn schedule_start(pool_config: &PoolConfig, start_at: u64) -> Result<u64> {
+    let current = current_point(pool_config)?;
+    if start_at == 0 {
+        let start_delay = if pool_config.time_based {
+            START_DELAY_SECONDS
+        } else {
+            START_DELAY_SLOTS
+        };
+        return Ok(current + start_delay);
+    }
+    let max_start = current + MAX_START_HORIZON_DAYS * points_per_day(pool_config);
+    require!(start_at > current && start_at <= max_start, BrewStakingError::InvalidStartTime);
+    Ok(start_at)
+}
+
+// Reward rate paying out `funding` evenly over the pool's duration.
+pub fn derive_reward_rate(pool_config: &PoolConfig, funding: u64) -> Result<u64> {
+    let points = (pool_config.duration as u64) * points_per_day(pool_config);
+    require!(points > 0, BrewStakingError::InvalidDuration);
+    let rate = funding / points;
+    require!(rate > 0, BrewStakingError::FundingTooLow);
+    Ok(rate)
+}
+
+pub fn set_reward_rate(pool_config: &mut PoolConfig, rate: u64) {
+    if pool_config.time_based {
+        pool_config.reward_per_second = rate;
+    } else {
+        pool_config.reward_per_slot = rate;
+    }
+}
+
+// The vault must hold everything the pool pays out until its end.
+pub fn require_funded(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &mut Account<PoolState>
+) -> Result<()> {
+    let shortfall = insufficient_rewards(pool_config, pool_state);
+    if shortfall > 0 {
+        msg!("Reward vault is short of {} reward tokens for the full duration", shortfall);
+        return err!(BrewStakingError::RewardNotDeposited);
+    }
+    Ok(())
+}
+
+// Set start and end of the emission window from the start point and the pool duration.
+pub fn set_schedule(pool_config: &mut PoolConfig, start: u64) {
+    let end = start + (pool_config.duration as u64) * points_per_day(pool_config);
+    if pool_config.time_based {
+        pool_config.start_time = start;
+        pool_config.end_time = end;
+    } else {
+        pool_config.start_slot = start;
+        pool_config.end_slot = end;
+    }
+}
+
+// Reward the user could claim right now, accruing the pool up to the current point
+// without writing anything back.
+pub fn pending_reward_of(
+    pool_config: &Account<PoolConfig>,
+    pool_state: &PoolState,
+    user_info: &UserInfo
+) -> Result<u64> {
+    let mut adjusted_token_per_share = pool_state.acc_token_per_share;
+
+    let current = current_point(pool_config)?;
+    let last_reward = last_reward_point(pool_config, pool_state);
+    let precision_factor = get_precision_factor(pool_config);
+
+    if current > last_reward && pool_state.total_working != 0 && last_reward > 0 {
+        let multiplier = get_multiplier(last_reward, current, end_point(pool_config));
+        let reward = multiplier * reward_rate(pool_config);
+
+        adjusted_token_per_share =
+            pool_state.acc_token_per_share + (reward * precision_factor) / pool_state.total_working;
+    }
+
+    Ok((user_info.working_amount * adjusted_token_per_share) / precision_factor - user_info.reward_debt)
+}
+
 pub fn get_multiplier(from_slot: u64, to_slot: u64, pool_end_slot: u64) -> u64 {
     if to_slot <= pool_end_slot {
         return to_slot - from_slot;
@@ -66,16 +959,16 @@ pub fn insufficient_rewards(
     let mut adjusted_should_total_paid = pool_state.should_total_paid;
     let remain_rewards = available_reward_tokens(pool_config, pool_state) + pool_state.paid_rewards;
 
-    if pool_config.start_slot == 0 {
+    if start_point(pool_config) == 0 {
         adjusted_should_total_paid +=
-            pool_config.reward_per_slot * (pool_config.duration as u64) * SLOTS_PER_DAY;
+            reward_rate(pool_config) * (pool_config.duration as u64) * points_per_day(pool_config);
     } else {
         let remain_blocks = get_multiplier(
-            pool_state.last_reward_slot,
-            pool_config.end_slot,
-            pool_config.end_slot
+            last_reward_point(pool_config, pool_state),
+            end_point(pool_config),
+            end_point(pool_config)
         );
-        adjusted_should_total_paid += pool_config.reward_per_slot * remain_blocks;
+        adjusted_should_total_paid += reward_rate(pool_config) * remain_blocks;
     }
 
     if remain_rewards >= adjusted_should_total_paid {
@@ -108,22 +1001,211 @@ macro_rules! require_lte {
     };
 }
 
-// pub fn transfer_tokens<'info>(
-//     from: AccountInfo<'info>,
-//     to: AccountInfo<'info>,
-//     authority: &AccountInfo<'info>,
-//     amount: u64,
-//     token_program: AccountInfo<'info>
-// ) -> Result<()> {
-//     let cpi_accounts = Transfer {
-//         from: from.to_account_info(),
-//         to: to.to_account_info(),
-//         authority: authority.to_account_info(),
-//     };
-//     let cpi_program = token_program.to_account_info();
-//     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-//     token::transfer(cpi_ctx, amount)
-// }
+pub fn transfer_tokens<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    token_program: AccountInfo<'info>
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from,
+        to,
+        authority,
+    };
+    let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)
+}
+// Performance fee charged on the pool's user actions.
+pub fn performance_fee_of(platform: &PlatformInfo, pool_config: &PoolConfig) -> u64 {
+    let discount = pool_config.performance_fee_discount_bps as u64;
+    platform.performance_fee - (platform.performance_fee * discount) / PERCENT_PRECISION
+}
+
+// Deploy fee rate in bps for a pool of `duration` days: the rate of the longest
+// duration tier it reaches, less the creator's discount.
+pub fn deploy_fee_bps(
+    fee_schedule: Option<&FeeSchedule>,
+    creator_tier: Option<&CreatorTier>,
+    duration: u16
+) -> u64 {
+    let mut rate = PERCENT_PRECISION;
+    if let Some(fee_schedule) = fee_schedule {
+        if
+            let Some(tier) = fee_schedule.duration_tiers
+                .iter()
+                .filter(|tier| tier.fee_bps > 0 && duration >= tier.min_days)
+                .max_by_key(|tier| tier.min_days)
+        {
+            rate = tier.fee_bps as u64;
+        }
+    }
+    if let Some(creator_tier) = creator_tier {
+        rate -= (rate * (creator_tier.deploy_fee_discount_bps as u64)) / PERCENT_PRECISION;
+    }
+    rate
+}
+
+// Registry page the pool at `position` is listed on.
+pub fn registry_page_of(position: u64) -> u32 {
+    (position / (REGISTRY_PAGE_LEN as u64)) as u32
+}
+
+// List a new pool in the platform registry and return its position there.
+pub fn register_pool(
+    platform: &mut PlatformInfo,
+    platform_key: Pubkey,
+    page: &mut RegistryPage,
+    entry: RegistryEntry
+) -> u64 {
+    let position = platform.pool_count;
+    page.platform = platform_key;
+    page.page = registry_page_of(position);
+    page.version = CURRENT_ACCOUNT_VERSION;
+    page.entries.push(entry);
+    platform.pool_count += 1;
+    position
+}
+
+// Add a registry position to a creator or stake mint listing.
+pub fn append_to_index(
+    index: &mut RegistryIndex,
+    page: &mut RegistryIndexPage,
+    platform_key: Pubkey,
+    key: Pubkey,
+    position: u64
+) {
+    index.platform = platform_key;
+    index.key = key;
+    index.version = CURRENT_ACCOUNT_VERSION;
+    page.key = key;
+    page.page = registry_page_of(index.count);
+    page.version = CURRENT_ACCOUNT_VERSION;
+    page.positions.push(position);
+    index.count += 1;
+}
+
+// Flag the pool's registry entry as closed.
+pub fn mark_registry_closed(
+    page: &mut RegistryPage,
+    pool_config: &PoolConfig,
+    pool_key: Pubkey
+) -> Result<()> {
+    let slot = (pool_config.registry_index % (REGISTRY_PAGE_LEN as u64)) as usize;
+    let entry = page.entries
+        .get_mut(slot)
+        .filter(|entry| entry.pool == pool_key)
+        .ok_or(error!(BrewStakingError::NotInRegistry))?;
+    entry.status = POOL_STATUS_CLOSED;
+    Ok(())
+}
+
+// Close a pool vault once it is empty, vaults still holding tokens may be
+// shared with other pools and are left alone. Returns whether it was closed.
+pub fn close_empty_vault<'info>(
+    vault: &Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<bool> {
+    if vault.amount > 0 {
+        return Ok(false);
+    }
+    let cpi_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination,
+        authority,
+    };
+    token::close_account(CpiContext::new(token_program, cpi_accounts))?;
+    Ok(true)
+}
+
+// Wrap lamports straight into a wSOL token account.
+pub fn wrap_sol<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    amount: u64,
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>
+) -> Result<()> {
+    let cpi_accounts = system_program::Transfer { from, to: to.clone() };
+    system_program::transfer(CpiContext::new(system_program, cpi_accounts), amount)?;
+    token::sync_native(CpiContext::new(token_program, SyncNative { account: to }))
+}
+
+// Pay wSOL out of a vault as lamports. The payer's temporary wSOL account at
+// [WSOL_SEED, payer] is created, filled and closed again within the instruction,
+// so the payer only fronts its rent; the amount is then passed on to the recipient.
+#[allow(clippy::too_many_arguments)]
+pub fn unwrap_sol<'info>(
+    mint: Pubkey,
+    vault: AccountInfo<'info>,
+    wsol_account: Option<AccountInfo<'info>>,
+    native_mint: Option<AccountInfo<'info>>,
+    authority: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    recipient: AccountInfo<'info>,
+    amount: u64,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>
+) -> Result<()> {
+    require_keys_eq!(mint, spl_token::native_mint::ID, BrewStakingError::NativeMintRequired);
+    let (Some(wsol_account), Some(native_mint)) = (wsol_account, native_mint) else {
+        return err!(BrewStakingError::NativeSolAccountsMissing);
+    };
+    require_keys_eq!(native_mint.key(), spl_token::native_mint::ID, ErrorCode::ConstraintAddress);
+
+    let (wsol_key, bump) = Pubkey::find_program_address(
+        &[WSOL_SEED, payer.key.as_ref()],
+        &crate::ID
+    );
+    require_keys_eq!(wsol_account.key(), wsol_key, ErrorCode::ConstraintSeeds);
+    let seeds: &[&[u8]] = &[WSOL_SEED, payer.key.as_ref(), &[bump]];
+
+    // Lamports sent to the address beforehand must not block the account
+    let space = spl_token::state::Account::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+    let top_up = rent.saturating_sub(wsol_account.lamports());
+    if top_up > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: wsol_account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.clone(), cpi_accounts), top_up)?;
+    }
+    let cpi_accounts = system_program::Allocate { account_to_allocate: wsol_account.clone() };
+    system_program::allocate(
+        CpiContext::new_with_signer(system_program.clone(), cpi_accounts, &[seeds]),
+        space as u64
+    )?;
+    let cpi_accounts = system_program::Assign { account_to_assign: wsol_account.clone() };
+    system_program::assign(
+        CpiContext::new_with_signer(system_program.clone(), cpi_accounts, &[seeds]),
+        token_program.key
+    )?;
+    let cpi_accounts = InitializeAccount3 {
+        account: wsol_account.clone(),
+        mint: native_mint,
+        authority: wsol_account.clone(),
+    };
+    token::initialize_account3(CpiContext::new(token_program.clone(), cpi_accounts))?;
+
+    transfer_tokens(vault, wsol_account.clone(), authority, amount, token_program.clone())?;
+
+    let cpi_accounts = CloseAccount {
+        account: wsol_account.clone(),
+        destination: payer.clone(),
+        authority: wsol_account,
+    };
+    token::close_account(CpiContext::new_with_signer(token_program, cpi_accounts, &[seeds]))?;
+
+    if recipient.key() != payer.key() {
+        let cpi_accounts = system_program::Transfer { from: payer, to: recipient };
+        system_program::transfer(CpiContext::new(system_program, cpi_accounts), amount)?;
+    }
+
+    Ok(())
+}
 /* 
 pub fn initialize_mint<'info>(
     token_program: AccountInfo<'info>,
@@ -21,10 +21,12 @@ import {
   getOrCreateAssociatedTokenAccount,
   createMint,
   mintTo,
+  NATIVE_MINT,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { createWithSeedSync } from "@coral-xyz/anchor/dist/cjs/utils/pubkey";
 import { assert } from "chai";
+import * as fs from "fs";
 import { StakingOnSolana } from "../target/types/staking_on_solana";
 import { createRandomMint, createRandomWalletAndAirdrop, getRandomNumber, waitSeconds } from "./utils";
 
@@ -79,8 +81,8 @@ describe("staking-on-solana", () => {
     //setup logging event listeners
     program.addEventListener('NewStartAndEndSlots', (event, slot) => {
       console.log('Event NewStartAndEndSlots in slot : ', slot);
-      console.log('start slot : ', event.startSlot.toString());
-      console.log('end slot : ', event.endSlot.toString());
+      console.log('start point : ', event.startPoint.toString());
+      console.log('end point : ', event.endPoint.toString());
     });
 
     program.addEventListener('RewardClaim', (event, slot) => {
@@ -177,7 +179,7 @@ describe("staking-on-solana", () => {
       }
 
       await program.methods
-        .startReward()
+        .startReward(new BN(0)) // start right away
         .accounts({
           deployer: deployer.publicKey,
           poolConfigAccount: pool.publicKey,
@@ -341,10 +343,15 @@ describe("staking-on-solana", () => {
         userInfo: userInfoPDA,
         userStakeTokenVault: userStakeTokenVault.address,
         userRewardTokenVault: userRewardTokenVault.address,
+        payoutWallet: null,
         poolStakeTokenVault: selected_pool.account.poolStakeTokenVault,
         poolRewardTokenVault: selected_pool.account.poolRewardTokenVault,
         creatorStakeTokenVault: creatorStakeTokenVault.address,
         treasuryStakeTokenVault: treasuryStakeTokenVault.address,
+        escrow: null,
+        escrowTotals: null,
+        wsolAccount: null,
+        nativeMint: null,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([admin.payer, user1])
@@ -434,6 +441,7 @@ describe("staking-on-solana", () => {
       .claimReward()
       .accounts({
         claimer: user1.publicKey,
+        owner: user1.publicKey,
         admin: admin.publicKey,
         treasury: treasury.publicKey,
         userInfo: userInfoPDA,
@@ -442,6 +450,11 @@ describe("staking-on-solana", () => {
         platform: platform_info_pda,
         userRewardTokenVault: userRewardTokenVault.address,
         poolRewardTokenVault: selected_pool.account.poolRewardTokenVault,
+        escrow: null,
+        escrowTotals: null,
+        payoutWallet: null,
+        wsolAccount: null,
+        nativeMint: null,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([admin.payer, user1])
@@ -513,9 +526,10 @@ describe("staking-on-solana", () => {
     );
 
     await program.methods
-      .compoundReward()
+      .compoundReward(new BN(1))
       .accounts({
         user: user1.publicKey,
+        owner: user1.publicKey,
         admin: admin.publicKey,
         treasury: treasury.publicKey,
         poolConfigAccount: selected_pool.publicKey,
@@ -525,9 +539,13 @@ describe("staking-on-solana", () => {
         poolStakeTokenVault: selected_pool.account.poolStakeTokenVault,
         poolRewardTokenVault: selected_pool.account.poolRewardTokenVault,
         // treasuryStakeTokenVault: treasuryStakeTokenVault.address,
+        escrow: null,
+        escrowTotals: null,
         tokenProgram: TOKEN_PROGRAM_ID,
         // raydium swap
         ammProgram: raydiumSwapParams.ammProgram,
+        stableAmmProgram: null,
+        modelDataAccount: null,
         amm: raydiumSwapParams.id,
         ammAuthority: raydiumSwapParams.authority,
         ammOpenOrders: raydiumSwapParams.openOrders,
@@ -552,11 +570,1227 @@ describe("staking-on-solana", () => {
 
   });
 
-  async function init_pool(deployer, duration, stakeFee, unstakeFee, initialFunding, rewardPerSlot, stakeMintDecimals, rewardMintDecimals) {
+  it("schedules a pool start in the future and rejects a past start", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    const currentSlot = await provider.connection.getSlot();
+
+    let failed = false;
+    await program.methods
+      .startReward(new BN(currentSlot - 1))
+      .accounts({
+        deployer: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "start in the past should be rejected");
+
+    const startAt = new BN(currentSlot + 1000);
+    await program.methods
+      .startReward(startAt)
+      .accounts({
+        deployer: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const scheduled = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.equal(scheduled.startSlot.toString(), startAt.toString(), "pool should start at the scheduled slot");
+  });
+
+  it("numbers pool events with a per-pool sequence", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    const created = await program.account.poolState.fetch(pool_config.stateAddr);
+    assert.equal(created.eventSeq.toString(), "1", "create_pool should emit the first pool event");
+
+    let started = null;
+    const listener = program.addEventListener('NewStartAndEndSlots', (event) => {
+      if (event.pool.equals(res.poolConfigAccountAddress)) {
+        started = event;
+      }
+    });
+
+    await program.methods
+      .startReward(new BN(0))
+      .accounts({
+        deployer: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const state = await program.account.poolState.fetch(pool_config.stateAddr);
+    assert.equal(state.eventSeq.toString(), "2", "start_reward should emit the next pool event");
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+    if (started) {
+      assert.equal(started.seq.toString(), "2");
+      assert.equal(started.version, 4);
+      assert.isFalse(started.timeBased, "slot pools report their schedule in slots");
+    }
+  });
+
+  it("reports a pool snapshot through get_pool_info", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    // Fetch the PDA of platform info account
+    const [platform_info_pda] = await PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const info = await program.methods
+      .getPoolInfo()
+      .accounts({
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        platform: platform_info_pda,
+      })
+      .view();
+
+    assert.isTrue(info.pool.equals(res.poolConfigAccountAddress));
+    assert.equal(info.startPoint.toString(), "0", "pool is not scheduled yet");
+    assert.equal(info.timeLeft.toString(), "0");
+    assert.equal(info.emissionRate.toString(), "0");
+    assert.equal(info.stakeFee, 200);
+    assert.equal(info.unstakeFee, 200);
+  });
+
+  it("lets only the pool owner disable stake_for", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    let failed = false;
+    await program.methods
+      .setStakeFor(false)
+      .accounts({
+        owner: user1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+      })
+      .signers([user1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "only the pool owner can toggle stake_for");
+
+    await program.methods
+      .setStakeFor(false)
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const updated = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.isTrue(updated.stakeForDisabled, "stake_for should be disabled");
+  });
+
+  it("switches an empty pool to position NFTs", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    await program.methods
+      .setPositionNftMode(true)
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const updated = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.isTrue(updated.positionNft, "pool should issue position NFTs");
+  });
+
+  it("configures reward vesting and keeps it apart from position NFTs", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    const accounts = {
+      owner: deployer1.publicKey,
+      poolConfigAccount: res.poolConfigAccountAddress,
+      poolStateAccount: pool_config.stateAddr,
+    };
+
+    await program.methods
+      .setVesting(new BN(216000), true)
+      .accounts(accounts)
+      .signers([deployer1])
+      .rpc();
+
+    const updated = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.equal(updated.vestingPeriod.toString(), "216000");
+    assert.isTrue(updated.redistributeForfeits);
+
+    let failed = false;
+    await program.methods
+      .setPositionNftMode(true)
+      .accounts(accounts)
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "vesting pools can't issue position NFTs");
+  });
+
+  it("turns on vote-escrow boosting for an empty pool", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const vault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      res.rewardMint,
+      admin.publicKey
+    );
+
+    // Only the platform treasury opens escrows on a mint
+    let failed = false;
+    await program.methods
+      .createEscrowTotals()
+      .accounts({
+        admin: admin.publicKey,
+        treasury: user1.publicKey,
+        platform: platform_info_pda,
+        mint: res.rewardMint,
+        escrowTotals: escrow_totals_address(platform_info_pda, res.rewardMint),
+        vault: vault.address,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "other wallets can't open escrows on a mint");
+
+    const escrowTotals = await create_escrow_totals(res.rewardMint);
+    const totals = await program.account.escrowTotals.fetch(escrowTotals);
+    assert.isTrue(totals.platform.equals(platform_info_pda));
+    assert.isTrue(totals.vault.equals(vault.address));
+
+    await program.methods
+      .setBoost(true)
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        escrowTotals,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const updated = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.isTrue(updated.boostEnabled, "pool should weigh stakes by vote-escrow balance");
+    assert.isTrue(updated.escrowTotals.equals(escrowTotals), "pool should be bound to the escrow totals");
+  });
+
+  it("rejects a successor pool with another stake token", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const other = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    let failed = false;
+    await program.methods
+      .setSuccessorPool()
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        successorPoolConfig: other.poolConfigAccountAddress,
+      })
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "successor must take the same stake token");
+  });
+
+  it("upgrades v1 pool and user accounts to the versioned layout", async () => {
+    // Loaded from tests/fixtures by the test validator, in the layout deployed before accounts had a version
+    const fixture = (name) => JSON.parse(fs.readFileSync(`tests/fixtures/${name}.json`, "utf8"));
+    const owner = Keypair.fromSecretKey(Uint8Array.from(fixture("v1-pool-owner")));
+    const poolConfigAddress = new PublicKey(fixture("v1-pool-config").pubkey);
+    const poolStateAddress = new PublicKey(fixture("v1-pool-state").pubkey);
+    const userInfoAddress = new PublicKey(fixture("v1-user-info").pubkey);
+
+    const signature = await provider.connection.requestAirdrop(owner.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(signature, "confirmed");
+
+    // Other wallets can't migrate a pool they don't own
+    let failed = false;
+    await program.methods
+      .migratePool()
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: poolConfigAddress,
+        poolStateAccount: poolStateAddress,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "only the pool owner may migrate the pool");
+
+    // Running the migration twice is a no-op
+    for (let i = 0; i < 2; i++) {
+      await program.methods
+        .migratePool()
+        .accounts({
+          owner: owner.publicKey,
+          poolConfigAccount: poolConfigAddress,
+          poolStateAccount: poolStateAddress,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    await program.methods
+      .migrateUserInfo()
+      .accounts({
+        user: owner.publicKey,
+        poolConfigAccount: poolConfigAddress,
+        poolStateAccount: poolStateAddress,
+        userInfo: userInfoAddress,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    const pool_config = await program.account.poolConfig.fetch(poolConfigAddress);
+    const pool_state = await program.account.poolState.fetch(poolStateAddress);
+    const user_info = await program.account.userInfo.fetch(userInfoAddress);
+
+    assert.equal(pool_config.version, 2, "pool config should be on layout v2");
+    assert.equal(pool_state.version, 2, "pool state should be on layout v2");
+    assert.equal(user_info.version, 2, "user info should be on layout v2");
+    assert.isTrue(pool_config.owner.equals(owner.publicKey), "owner should survive the migration");
+    assert.isTrue(pool_config.stateAddr.equals(poolStateAddress), "state address should survive the migration");
+    assert.equal(pool_config.rewardPerSlot.toNumber(), 1000);
+    assert.equal(pool_state.totalStaked.toNumber(), 1000);
+    assert.equal(pool_state.rewardAmount.toNumber(), 5000);
+    assert.equal(pool_state.eventSeq.toNumber(), 2, "the pool and user migrations should each emit one pool event");
+    assert.equal(pool_state.totalWorking.toNumber(), 1000, "unboosted pools share rewards on the plain stake");
+    assert.equal(user_info.stakedAmount.toNumber(), 1000);
+    assert.equal(user_info.workingAmount.toNumber(), 1000, "working amount should start at the stake");
+  });
+
+  it("sweeps stray tokens but never the pool's own funds", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    // Tokens of an unrelated mint sent to the vault authority
+    const strayMint = await createRandomMint(provider, 6);
+    const strayAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      strayMint,
+      admin.publicKey
+    );
+    await mintTo(provider.connection, admin.payer, strayMint, strayAccount.address, admin.publicKey, 500);
+    const ownerStrayAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      strayMint,
+      deployer1.publicKey
+    );
+
+    await program.methods
+      .sweepTokens(new BN(500))
+      .accounts({
+        owner: deployer1.publicKey,
+        admin: admin.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        source: strayAccount.address,
+        destination: ownerStrayAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const swept = await getAccount(provider.connection, ownerStrayAccount.address);
+    assert.equal(Number(swept.amount), 500, "stray tokens should reach the pool owner");
+
+    // The reward vault holds exactly the funded rewards, so there is no surplus
+    let failed = false;
+    await program.methods
+      .sweepTokens(new BN(1))
+      .accounts({
+        owner: deployer1.publicKey,
+        admin: admin.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        source: res.poolRewardTokenVault.address,
+        destination: res.creatorRewardTokenVault.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "owed rewards must not be swept");
+  });
+
+  it("stakes and unstakes native SOL without wrapping by hand", async () => {
+    const rewardMint = await createRandomMint(provider, 9);
+    const fundingAmount = new BN(10 ** 9 * 14);
+    const ata = (mint, owner) => getOrCreateAssociatedTokenAccount(provider.connection, admin.payer, mint, owner);
+
+    const creatorRewardTokenVault = await ata(rewardMint, deployer1.publicKey);
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      rewardMint,
+      creatorRewardTokenVault.address,
+      admin.publicKey,
+      BigInt(fundingAmount.toString())
+    );
+    const poolStakeTokenVault = await ata(NATIVE_MINT, admin.publicKey);
+    const poolRewardTokenVault = await ata(rewardMint, admin.publicKey);
+    const creatorStakeTokenVault = await ata(NATIVE_MINT, deployer1.publicKey);
+    const treasuryStakeTokenVault = await ata(NATIVE_MINT, treasury.publicKey);
+    const userRewardTokenVault = await ata(rewardMint, user2.publicKey);
+
+    const poolConfigAccount = Keypair.generate();
+    const poolStateAccount = Keypair.generate();
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createPool(0, 0, fundingAmount, new BN(1000), 30, false, false)
+      .accounts({
+        poolConfigAccount: poolConfigAccount.publicKey,
+        poolStateAccount: poolStateAccount.publicKey,
+        platform: platform_info_pda,
+        creator: deployer1.publicKey,
+        treasury: treasury.publicKey,
+        stakeMint: NATIVE_MINT,
+        rewardMint,
+        poolStakeTokenVault: poolStakeTokenVault.address,
+        poolRewardTokenVault: poolRewardTokenVault.address,
+        creatorRewardTokenVault: creatorRewardTokenVault.address,
+        ...(await registry_accounts(deployer1.publicKey, NATIVE_MINT)),
+        ...(await fee_accounts(deployer1.publicKey)),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1, poolConfigAccount, poolStateAccount])
+      .rpc();
+
+    await program.methods
+      .startReward(new BN(0))
+      .accounts({
+        deployer: deployer1.publicKey,
+        poolConfigAccount: poolConfigAccount.publicKey,
+        poolStateAccount: poolStateAccount.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const [userInfoPDA] = PublicKey.findProgramAddressSync(
+      [poolConfigAccount.publicKey.toBuffer(), user2.publicKey.toBuffer()],
+      program.programId
+    );
+    const [wsolAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("wsol"), user2.publicKey.toBuffer()],
+      program.programId
+    );
+    const vaultBefore = await getAccount(provider.connection, poolStakeTokenVault.address);
+    const stakeAmount = new BN(LAMPORTS_PER_SOL / 2);
+
+    // Lamports go straight into the pool's wSOL vault
+    await program.methods
+      .stake(stakeAmount)
+      .accounts({
+        staker: user2.publicKey,
+        admin: admin.publicKey,
+        treasury: treasury.publicKey,
+        platform: platform_info_pda,
+        userInfo: userInfoPDA,
+        userStakeTokenVault: null,
+        userRewardTokenVault: userRewardTokenVault.address,
+        payoutWallet: null,
+        poolStakeTokenVault: poolStakeTokenVault.address,
+        poolRewardTokenVault: poolRewardTokenVault.address,
+        creatorStakeTokenVault: creatorStakeTokenVault.address,
+        treasuryStakeTokenVault: treasuryStakeTokenVault.address,
+        poolConfigAccount: poolConfigAccount.publicKey,
+        poolStateAccount: poolStateAccount.publicKey,
+        escrow: null,
+        escrowTotals: null,
+        wsolAccount: null,
+        nativeMint: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user2])
+      .rpc();
+
+    const vaultAfter = await getAccount(provider.connection, poolStakeTokenVault.address);
+    assert.equal(
+      (vaultAfter.amount - vaultBefore.amount).toString(),
+      stakeAmount.toString(),
+      "staked lamports should be wrapped into the vault"
+    );
+
+    const balanceBefore = await provider.connection.getBalance(user2.publicKey);
+    await program.methods
+      .unstake(stakeAmount.divn(2))
+      .accounts({
+        user: user2.publicKey,
+        admin: admin.publicKey,
+        treasury: treasury.publicKey,
+        poolConfigAccount: poolConfigAccount.publicKey,
+        poolStateAccount: poolStateAccount.publicKey,
+        platform: platform_info_pda,
+        userInfo: userInfoPDA,
+        userStakeTokenVault: null,
+        userRewardTokenVault: userRewardTokenVault.address,
+        payoutWallet: null,
+        poolStakeTokenVault: poolStakeTokenVault.address,
+        poolRewardTokenVault: poolRewardTokenVault.address,
+        creatorStakeTokenVault: creatorStakeTokenVault.address,
+        treasuryStakeTokenVault: treasuryStakeTokenVault.address,
+        escrow: null,
+        escrowTotals: null,
+        wsolAccount,
+        nativeMint: NATIVE_MINT,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+
+    const balanceAfter = await provider.connection.getBalance(user2.publicKey);
+    assert.isAbove(balanceAfter, balanceBefore, "unstaked SOL should come back as lamports");
+    assert.isNull(
+      await provider.connection.getAccountInfo(wsolAccount),
+      "temporary wSOL account should be closed"
+    );
+  });
+
+  it("lists new pools in the registry by creator and stake mint", async () => {
+    const res = await init_pool(deployer2, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // The stake mint is fresh, so its listing holds just this pool
+    const [stakeMintIndex] = PublicKey.findProgramAddressSync(
+      [Buffer.from("registry"), Buffer.from("stake_mint"), platform_info_pda.toBuffer(), res.stakeMint.toBuffer()],
+      program.programId
+    );
+    const [stakeMintIndexPage] = PublicKey.findProgramAddressSync(
+      [Buffer.from("registry"), Buffer.from("stake_mint"), platform_info_pda.toBuffer(), res.stakeMint.toBuffer(), registry_page_seed(0)],
+      program.programId
+    );
+    const mint_index = await program.account.registryIndex.fetch(stakeMintIndex);
+    const mint_page = await program.account.registryIndexPage.fetch(stakeMintIndexPage);
+    assert.equal(mint_index.count.toNumber(), 1);
+    assert.equal(mint_page.positions[0].toNumber(), pool_config.registryIndex.toNumber());
+
+    const registryPage = registry_page_address(platform_info_pda, pool_config.registryIndex.toNumber());
+    const page = await program.account.registryPage.fetch(registryPage);
+    const entry = page.entries[pool_config.registryIndex.toNumber() % 32];
+    assert.isTrue(entry.pool.equals(res.poolConfigAccountAddress), "registry should point at the pool");
+    assert.isTrue(entry.creator.equals(deployer2.publicKey));
+    assert.equal(entry.status, 0);
+
+    // A pool that never started can be retired right away
+    await program.methods
+      .markPoolClosed()
+      .accounts({
+        owner: deployer2.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        platform: platform_info_pda,
+        registryPage,
+      })
+      .signers([deployer2])
+      .rpc();
+
+    const closed = await program.account.registryPage.fetch(registryPage);
+    assert.equal(closed.entries[pool_config.registryIndex.toNumber() % 32].status, 1, "pool should be marked closed");
+  });
+
+  it("discounts fees for partner creators", async () => {
+    const partner = await createRandomWalletAndAirdrop(provider, 2);
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const [creatorTier] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_tier"), platform_info_pda.toBuffer(), partner.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .setCreatorTier(5000, 2500)
+      .accounts({
+        treasury: treasury.publicKey,
+        platform: platform_info_pda,
+        creator: partner.publicKey,
+        creatorTier,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([treasury])
+      .rpc();
+
+    const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+    const res = await init_pool(partner, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const treasuryAfter = await provider.connection.getBalance(treasury.publicKey);
+    assert.equal(treasuryAfter - treasuryBefore, deploy_fee.toNumber() / 2, "partner pays half the deploy fee");
+
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.equal(pool_config.performanceFeeDiscountBps, 2500, "pool keeps the partner's performance fee discount");
+  });
+
+  it("cancels a pool that never started and refunds its funding", async () => {
+    const res = await init_pool(deployer2, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    const pool_state = await program.account.poolState.fetch(pool_config.stateAddr);
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const [feeSchedule] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_schedule"), platform_info_pda.toBuffer()],
+      program.programId
+    );
+    const before = await getAccount(provider.connection, res.creatorRewardTokenVault.address);
+
+    await program.methods
+      .cancelPool()
+      .accounts({
+        owner: deployer2.publicKey,
+        admin: admin.publicKey,
+        treasury: null,
+        platform: platform_info_pda,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        registryPage: registry_page_address(platform_info_pda, pool_config.registryIndex.toNumber()),
+        feeSchedule,
+        poolStakeTokenVault: pool_config.poolStakeTokenVault,
+        poolRewardTokenVault: pool_config.poolRewardTokenVault,
+        creatorRewardTokenVault: res.creatorRewardTokenVault.address,
+        wsolAccount: null,
+        nativeMint: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([deployer2])
+      .rpc();
+
+    const after = await getAccount(provider.connection, res.creatorRewardTokenVault.address);
+    assert.equal(
+      (after.amount - before.amount).toString(),
+      pool_state.rewardAmount.toString(),
+      "creator should get the whole funding back"
+    );
+    assert.isNull(
+      await provider.connection.getAccountInfo(res.poolConfigAccountAddress),
+      "pool config should be closed"
+    );
+    assert.isNull(
+      await provider.connection.getAccountInfo(pool_config.poolRewardTokenVault),
+      "emptied reward vault should be closed"
+    );
+  });
+
+  it("keeps a running pool from being closed", async () => {
+    const res = await init_pool(deployer2, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .startReward(new BN(0))
+      .accounts({
+        deployer: deployer2.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer2])
+      .rpc();
+
+    let failed = false;
+    await program.methods
+      .closePool()
+      .accounts({
+        owner: deployer2.publicKey,
+        admin: admin.publicKey,
+        platform: platform_info_pda,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        registryPage: registry_page_address(platform_info_pda, pool_config.registryIndex.toNumber()),
+        poolStakeTokenVault: pool_config.poolStakeTokenVault,
+        poolRewardTokenVault: pool_config.poolRewardTokenVault,
+        creatorRewardTokenVault: res.creatorRewardTokenVault.address,
+        wsolAccount: null,
+        nativeMint: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([deployer2])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "pool can only be closed after it ended");
+    assert.isNotNull(await provider.connection.getAccountInfo(res.poolConfigAccountAddress));
+  });
+
+  it("only opens a new season once the pool ended", async () => {
+    const res = await init_pool(deployer2, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    await program.methods
+      .startReward(new BN(0))
+      .accounts({
+        deployer: deployer2.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer2])
+      .rpc();
+    const started = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    let failed = false;
+    await program.methods
+      .startSeason(14, new BN(0), new BN(1_000_000_000), new BN(0))
+      .accounts({
+        owner: deployer2.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        poolRewardTokenVault: pool_config.poolRewardTokenVault,
+        ownerRewardTokenVault: res.creatorRewardTokenVault.address,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer2])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "a season can only follow an ended one");
+
+    const after = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.equal(after.season, 0);
+    assert.equal(after.endSlot.toString(), started.endSlot.toString(), "running schedule is kept");
+  });
+
+  it("carries positions into a new season without accruing in the gap", async () => {
+    const res = await init_pool(deployer2, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool = await start_pool(deployer2, res.poolConfigAccountAddress);
+    const [userInfoAddress] = PublicKey.findProgramAddressSync(
+      [pool.publicKey.toBuffer(), user1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await stake_pool(pool, user1, 0.02);
+    await new Promise((resolve) => setTimeout(resolve, 6000));
+
+    // Only the pool owner may stop it
+    let failed = false;
+    await program.methods
+      .stopReward()
+      .accounts({
+        owner: deployer1.publicKey,
+        deployer: admin.publicKey,
+        poolConfigAccount: pool.publicKey,
+        poolRewardTokenVault: pool.account.poolRewardTokenVault,
+        deployerRewardTokenVault: res.creatorRewardTokenVault.address,
+        poolStateAccount: pool.account.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "other wallets can't end the pool");
+
+    // End the first season early rather than waiting out its 30 days
+    await program.methods
+      .stopReward()
+      .accounts({
+        owner: deployer2.publicKey,
+        deployer: admin.publicKey,
+        poolConfigAccount: pool.publicKey,
+        poolRewardTokenVault: pool.account.poolRewardTokenVault,
+        deployerRewardTokenVault: res.creatorRewardTokenVault.address,
+        poolStateAccount: pool.account.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer2])
+      .rpc();
+    const ended = await program.account.poolConfig.fetch(pool.publicKey);
+    assert.isBelow(ended.endSlot.toNumber(), pool.account.endSlot.toNumber(), "the season should end now");
+
+    const atEnd = await program.account.poolState.fetch(pool.account.stateAddr);
+    assert.isAbove(atEnd.accTokenPerShare.toNumber(), 0, "the first season should have paid out");
+
+    // Nothing accrues while the pool sits between seasons
+    await new Promise((resolve) => setTimeout(resolve, 6000));
+    await stake_pool(pool, user1, 0.02);
+    const inGap = await program.account.poolState.fetch(pool.account.stateAddr);
+    assert.equal(inGap.accTokenPerShare.toString(), atEnd.accTokenPerShare.toString(), "no rewards between seasons");
+    const before = await program.account.userInfo.fetch(userInfoAddress);
+
+    await program.methods
+      .startSeason(1, new BN(15000), new BN(15000).muln(216000), new BN(0))
+      .accounts({
+        owner: deployer2.publicKey,
+        poolConfigAccount: pool.publicKey,
+        poolStateAccount: pool.account.stateAddr,
+        poolRewardTokenVault: pool.account.poolRewardTokenVault,
+        ownerRewardTokenVault: res.creatorRewardTokenVault.address,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer2])
+      .rpc();
+
+    const season = await program.account.poolConfig.fetch(pool.publicKey);
+    const resumed = await program.account.poolState.fetch(pool.account.stateAddr);
+    assert.equal(season.season, 1);
+    assert.isAbove(season.startSlot.toNumber(), ended.endSlot.toNumber(), "the new season starts after the gap");
+    assert.equal(resumed.lastRewardSlot.toString(), season.startSlot.toString(), "accrual resumes at the new start");
+    assert.equal(resumed.accTokenPerShare.toString(), atEnd.accTokenPerShare.toString(), "the gap stays unpaid");
+
+    // The position carries over and earns again once the season runs
+    const after = await program.account.userInfo.fetch(userInfoAddress);
+    assert.equal(after.stakedAmount.toString(), before.stakedAmount.toString(), "positions survive the new season");
+    await new Promise((resolve) => setTimeout(resolve, 6000));
+    await stake_pool(pool, user1, 0.02);
+    const running = await program.account.poolState.fetch(pool.account.stateAddr);
+    assert.isAbove(
+      running.accTokenPerShare.toNumber(),
+      atEnd.accTokenPerShare.toNumber(),
+      "carried positions should earn in the new season"
+    );
+  });
+
+  it("configures a decaying early-exit penalty", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    const accounts = {
+      owner: deployer1.publicKey,
+      poolConfigAccount: res.poolConfigAccountAddress,
+      poolStateAccount: pool_config.stateAddr,
+    };
+
+    await program.methods
+      .setEarlyExitPenalty(1000, 30, false)
+      .accounts(accounts)
+      .signers([deployer1])
+      .rpc();
+
+    const updated = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.equal(updated.earlyExitPenaltyBps, 1000);
+    assert.equal(updated.earlyExitPenaltyDays, 30);
+    assert.isFalse(updated.redistributePenalties);
+
+    let failed = false;
+    await program.methods
+      .setEarlyExitPenalty(1000, 30, true)
+      .accounts(accounts)
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "penalties in the stake token can't be paid out as rewards");
+
+    failed = false;
+    await program.methods
+      .setEarlyExitPenalty(1000, 0, false)
+      .accounts(accounts)
+      .signers([deployer1])
+      .rpc()
+      .catch(() => { failed = true; });
+    assert.isTrue(failed, "a penalty needs a decay period");
+  });
+
+  it("decays the early-exit penalty and ages top-ups into the deposit slot", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const created = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    await program.methods
+      .setEarlyExitPenalty(1000, 1, false)
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: created.stateAddr,
+      })
+      .signers([deployer1])
+      .rpc();
+    const pool = await start_pool(deployer1, res.poolConfigAccountAddress);
+    const [userInfoAddress] = PublicKey.findProgramAddressSync(
+      [pool.publicKey.toBuffer(), user1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await stake_pool(pool, user1, 1);
+    const first = await program.account.userInfo.fetch(userInfoAddress);
+
+    // A top-up moves the deposit forward by its share of the position, not to now
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await stake_pool(pool, user1, 1);
+    const topUpSlot = await provider.connection.getSlot();
+    const toppedUp = await program.account.userInfo.fetch(userInfoAddress);
+    assert.isAbove(toppedUp.depositSlot.toNumber(), first.depositSlot.toNumber(), "a top-up should age the position");
+    assert.isBelow(toppedUp.depositSlot.toNumber(), topUpSlot, "older stake should keep its head start");
+
+    // The penalty has decayed a little since the deposit, but is still charged
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    const staked = toppedUp.stakedAmount;
+    const creatorStakeTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool.account.stakeMint,
+      pool.account.owner
+    );
+    const creatorBefore = new BN((await provider.connection.getTokenAccountBalance(creatorStakeTokenVault.address)).value.amount);
+    await unstake_pool(pool, user1, staked);
+    const creatorAfter = new BN((await provider.connection.getTokenAccountBalance(creatorStakeTokenVault.address)).value.amount);
+
+    const penalty = creatorAfter.sub(creatorBefore);
+    const fullPenalty = staked.muln(1000).divn(10000);
+    assert.isTrue(penalty.gtn(0), "an early exit should pay the penalty");
+    assert.isTrue(penalty.lt(fullPenalty), "the penalty should decay from the deposit on");
+  });
+
+  it("redistributes early-exit penalties to the remaining stakers", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(1), 6, 6, false, true);
+    const created = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    await program.methods
+      .setEarlyExitPenalty(1000, 1, true)
+      .accounts({
+        owner: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: created.stateAddr,
+      })
+      .signers([deployer1])
+      .rpc();
+    const pool = await start_pool(deployer1, res.poolConfigAccountAddress);
+    const user_info_address = (user) => PublicKey.findProgramAddressSync(
+      [pool.publicKey.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await stake_pool(pool, user1, 1);
+    await stake_pool(pool, user2, 1);
+
+    const before = await program.account.poolState.fetch(pool.account.stateAddr);
+    const remaining = await program.account.userInfo.fetch(user_info_address(user1));
+    const leaving = await program.account.userInfo.fetch(user_info_address(user2));
+    const creatorStakeTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool.account.stakeMint,
+      pool.account.owner
+    );
+    const creatorBefore = new BN((await provider.connection.getTokenAccountBalance(creatorStakeTokenVault.address)).value.amount);
+    await unstake_pool(pool, user2, leaving.stakedAmount);
+    const creatorAfter = new BN((await provider.connection.getTokenAccountBalance(creatorStakeTokenVault.address)).value.amount);
+    const after = await program.account.poolState.fetch(pool.account.stateAddr);
+
+    assert.equal(creatorAfter.toString(), creatorBefore.toString(), "redistributed penalties skip the creator");
+
+    // Nearly the whole penalty lands on the one staker left, 10^(9 - 6) precision
+    const share = leaving.stakedAmount.muln(1000).divn(10000).muln(1000).div(remaining.workingAmount);
+    const gained = after.accTokenPerShare.sub(before.accTokenPerShare);
+    assert.isTrue(gained.gte(share.muln(99).divn(100)), "the penalty should raise the remaining stakers' share");
+  });
+
+  it("pays delegated claims to the reward recipient and rejects other signers", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8);
+    const pool = await start_pool(deployer1, res.poolConfigAccountAddress);
+    await stake_pool(pool, user1, 0.02);
+
+    const [userInfoPDA] = PublicKey.findProgramAddressSync(
+      [pool.publicKey.toBuffer(), user1.publicKey.toBuffer()],
+      program.programId
+    );
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const recipient = Keypair.generate();
+    await program.methods
+      .setClaimSettings(recipient.publicKey, user2.publicKey)
+      .accounts({
+        user: user1.publicKey,
+        userInfo: userInfoPDA,
+        poolConfigAccount: pool.publicKey,
+        poolStateAccount: pool.account.stateAddr,
+      })
+      .signers([user1])
+      .rpc();
+
+    const recipientRewardTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool.account.rewardMint,
+      recipient.publicKey
+    );
+    const ownerRewardTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool.account.rewardMint,
+      user1.publicKey
+    );
+    await waitSeconds(2);
+
+    const claim = (claimer, userRewardTokenVault) => program.methods
+      .claimReward()
+      .accounts({
+        claimer: claimer.publicKey,
+        owner: user1.publicKey,
+        admin: admin.publicKey,
+        treasury: treasury.publicKey,
+        userInfo: userInfoPDA,
+        poolConfigAccount: pool.publicKey,
+        poolStateAccount: pool.account.stateAddr,
+        platform: platform_info_pda,
+        userRewardTokenVault,
+        poolRewardTokenVault: pool.account.poolRewardTokenVault,
+        escrow: null,
+        escrowTotals: null,
+        payoutWallet: null,
+        wsolAccount: null,
+        nativeMint: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([admin.payer, claimer])
+      .rpc();
+
+    let failed = false;
+    await claim(deployer2, recipientRewardTokenVault.address).catch(() => { failed = true; });
+    assert.isTrue(failed, "only the owner or its delegate can claim");
+
+    failed = false;
+    await claim(user2, ownerRewardTokenVault.address).catch(() => { failed = true; });
+    assert.isTrue(failed, "rewards can only go to the recipient");
+
+    await claim(user2, recipientRewardTokenVault.address);
+    const received = await getAccount(provider.connection, recipientRewardTokenVault.address);
+    assert.isTrue(received.amount > BigInt(0), "the delegate's claim should reach the recipient");
+  });
+
+  function escrow_totals_address(platform, mint) {
+    const [escrowTotals] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_totals"), platform.toBuffer(), mint.toBuffer()],
+      program.programId
+    );
+    return escrowTotals;
+  }
+
+  // Escrow totals of the test platform on `mint`, locked tokens go to an admin vault
+  async function create_escrow_totals(mint) {
+    const [platform] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const vault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      admin.publicKey
+    );
+    const escrowTotals = escrow_totals_address(platform, mint);
+    await program.methods
+      .createEscrowTotals()
+      .accounts({
+        admin: admin.publicKey,
+        treasury: treasury.publicKey,
+        platform,
+        mint,
+        escrowTotals,
+        vault: vault.address,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([treasury])
+      .rpc();
+    return escrowTotals;
+  }
+
+  async function start_pool(deployer, poolConfigAccountAddress) {
+    const pool_config = await program.account.poolConfig.fetch(poolConfigAccountAddress);
+    await program.methods
+      .startReward(new BN(0))
+      .accounts({
+        deployer: deployer.publicKey,
+        poolConfigAccount: poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer])
+      .rpc();
+    return {
+      publicKey: poolConfigAccountAddress,
+      account: await program.account.poolConfig.fetch(poolConfigAccountAddress),
+    };
+  }
+
+  function registry_page_seed(position) {
+    const seed = Buffer.alloc(4);
+    seed.writeUInt32LE(Math.floor(position / 32));
+    return seed;
+  }
+
+  function registry_page_address(platform, position) {
+    const [page] = PublicKey.findProgramAddressSync(
+      [Buffer.from("registry"), platform.toBuffer(), registry_page_seed(position)],
+      program.programId
+    );
+    return page;
+  }
+
+  // Registry pages and listings create_pool appends the next pool to
+  async function registry_accounts(creator, stakeMint) {
+    const [platform] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const platform_info = await program.account.platformInfo.fetch(platform);
+
+    const listing = async (kind, key) => {
+      const [index] = PublicKey.findProgramAddressSync(
+        [Buffer.from("registry"), Buffer.from(kind), platform.toBuffer(), key.toBuffer()],
+        program.programId
+      );
+      const existing = await program.account.registryIndex.fetchNullable(index);
+      const count = existing ? existing.count.toNumber() : 0;
+      const [page] = PublicKey.findProgramAddressSync(
+        [Buffer.from("registry"), Buffer.from(kind), platform.toBuffer(), key.toBuffer(), registry_page_seed(count)],
+        program.programId
+      );
+      return [index, page];
+    };
+    const [creatorIndex, creatorIndexPage] = await listing("creator", creator);
+    const [stakeMintIndex, stakeMintIndexPage] = await listing("stake_mint", stakeMint);
+
+    return {
+      registryPage: registry_page_address(platform, platform_info.poolCount.toNumber()),
+      creatorIndex,
+      creatorIndexPage,
+      stakeMintIndex,
+      stakeMintIndexPage,
+    };
+  }
+
+  it("derives the reward rate from the initial funding", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(0), 6, 8);
+    const pool_config = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+
+    // 14 tokens spread over 30 days of slots
+    const expected = new BN(14 * 10 ** 6).divn(30 * 216000);
+    assert.equal(pool_config.rewardPerSlot.toString(), expected.toString(), "rate should follow the funding");
+
+    await program.methods
+      .startReward(new BN(0))
+      .accounts({
+        deployer: deployer1.publicKey,
+        poolConfigAccount: res.poolConfigAccountAddress,
+        poolStateAccount: pool_config.stateAddr,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([deployer1])
+      .rpc();
+
+    const started = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.isAbove(started.startSlot.toNumber(), 0, "fully funded pool should start");
+  });
+
+  it("runs a time-based pool on unix timestamps", async () => {
+    const res = await init_pool(deployer1, 30, 200, 200, 14, new BN(15000), 6, 8, true);
+    const created = await program.account.poolConfig.fetch(res.poolConfigAccountAddress);
+    assert.isTrue(created.timeBased);
+    assert.equal(created.rewardPerSecond.toNumber(), 15000, "time-based pools pay per second");
+    assert.equal(created.rewardPerSlot.toNumber(), 0);
+
+    let started = null;
+    const listener = program.addEventListener('NewStartAndEndSlots', (event) => {
+      if (event.pool.equals(res.poolConfigAccountAddress)) {
+        started = event;
+      }
+    });
+
+    const pool = await start_pool(deployer1, res.poolConfigAccountAddress);
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    assert.equal(pool.account.startSlot.toNumber(), 0, "time-based pools keep no slot schedule");
+    assert.isAtLeast(pool.account.startTime.toNumber(), now, "start should be a unix timestamp");
+    assert.equal(
+      pool.account.endTime.sub(pool.account.startTime).toNumber(),
+      30 * 86400,
+      "the pool should run for its duration in seconds"
+    );
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+    if (started) {
+      assert.isTrue(started.timeBased, "the event should flag its points as timestamps");
+      assert.equal(started.startPoint.toString(), pool.account.startTime.toString());
+      assert.equal(started.endPoint.toString(), pool.account.endTime.toString());
+    }
+
+    // Let the start delay pass, then a second stake accrues the elapsed seconds
+    await stake_pool(pool, user1, 0.02);
+    await new Promise((resolve) => setTimeout(resolve, 6000));
+    await stake_pool(pool, user1, 0.02);
+
+    const state = await program.account.poolState.fetch(pool.account.stateAddr);
+    assert.equal(state.lastRewardSlot.toNumber(), 0, "time-based pools don't track slots");
+    assert.isAbove(state.lastRewardTime.toNumber(), pool.account.startTime.toNumber(), "accrual should follow the clock");
+    assert.isAbove(state.accTokenPerShare.toNumber(), 0, "stakers should earn over time");
+  });
+
+  // Fee schedule and creator tier create_pool reads, paying the deploy fee in lamports
+  async function fee_accounts(creator) {
+    const [platform] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+    const [feeSchedule] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_schedule"), platform.toBuffer()],
+      program.programId
+    );
+    const [creatorTier] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_tier"), platform.toBuffer(), creator.toBuffer()],
+      program.programId
+    );
+    const tier = await program.account.creatorTier.fetchNullable(creatorTier);
+
+    return {
+      feeSchedule,
+      creatorTier: tier ? creatorTier : null,
+      creatorFeeTokenAccount: null,
+      treasuryFeeTokenAccount: null,
+    };
+  }
+
+  async function init_pool(deployer, duration, stakeFee, unstakeFee, initialFunding, rewardPerSlot, stakeMintDecimals, rewardMintDecimals, timeBased = false, singleMint = false) {
     // Create a new mint for mock stake token
     const stakeMint = await createRandomMint(provider, stakeMintDecimals)
-    // Create a new mint for mock reward token
-    const rewardMint = await createRandomMint(provider, rewardMintDecimals)
+    // Create a new mint for mock reward token, or pay rewards in the stake token
+    const rewardMint = singleMint ? stakeMint : await createRandomMint(provider, rewardMintDecimals)
 
     // Create a reward token account for the pool creator
     const creatorRewardTokenVault = await getOrCreateAssociatedTokenAccount(
@@ -566,7 +1800,11 @@ describe("staking-on-solana", () => {
       deployer.publicKey
     );
 
-    const fundingAmount = new BN(10 ** stakeMintDecimals * initialFunding)
+    // Fund at least the full duration, start_reward refuses pools that are short
+    const fundingAmount = BN.max(
+      new BN(10 ** stakeMintDecimals * initialFunding),
+      rewardPerSlot.muln(duration).muln(216000)
+    )
 
     // Mint some mock reward token to the pool creator's account
     await mintTo(
@@ -626,7 +1864,9 @@ describe("staking-on-solana", () => {
         unstakeFee,
         fundingAmount,
         rewardPerSlot,
-        duration
+        duration,
+        false, // useStableSwap
+        timeBased
       )
       .accounts({
         // poolConfigAccount: POOL_CONFIG_PDA,
@@ -642,6 +1882,8 @@ describe("staking-on-solana", () => {
         // poolStakeTokenVault: poolStakeTokenVault,
         // poolRewardTokenVault: poolRewardTokenVault,
         creatorRewardTokenVault: creatorRewardTokenVault.address,
+        ...(await registry_accounts(deployer.publicKey, stakeMint)),
+        ...(await fee_accounts(deployer.publicKey)),
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
@@ -733,12 +1975,17 @@ describe("staking-on-solana", () => {
         userInfo: userInfoPDA,
         userStakeTokenVault: userStakeTokenVault.address,
         userRewardTokenVault: userRewardTokenVault.address,
+        payoutWallet: null,
         poolStakeTokenVault: pool_config.account.poolStakeTokenVault,
         poolRewardTokenVault: pool_config.account.poolRewardTokenVault,
         creatorStakeTokenVault: creatorStakeTokenVault.address,
         treasuryStakeTokenVault: treasuryStakeTokenVault.address,
         poolConfigAccount: pool_config.publicKey,
         poolStateAccount: pool_config.account.stateAddr,
+        escrow: null,
+        escrowTotals: null,
+        wsolAccount: null,
+        nativeMint: null,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([user])
@@ -754,4 +2001,65 @@ describe("staking-on-solana", () => {
 
   }
 
+  async function unstake_pool(pool_config, user, unstakeAmount) {
+    const userStakeTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool_config.account.stakeMint,
+      user.publicKey
+    );
+    const userRewardTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool_config.account.rewardMint,
+      user.publicKey
+    );
+    const creatorStakeTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool_config.account.stakeMint,
+      pool_config.account.owner
+    );
+    const treasuryStakeTokenVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      pool_config.account.stakeMint,
+      treasury.publicKey
+    );
+    const [userInfoPDA] = PublicKey.findProgramAddressSync(
+      [pool_config.publicKey.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const [platform_info_pda] = PublicKey.findProgramAddressSync(
+      [treasury.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .unstake(unstakeAmount)
+      .accounts({
+        user: user.publicKey,
+        admin: admin.publicKey,
+        treasury: treasury.publicKey,
+        poolConfigAccount: pool_config.publicKey,
+        poolStateAccount: pool_config.account.stateAddr,
+        platform: platform_info_pda,
+        userInfo: userInfoPDA,
+        userStakeTokenVault: userStakeTokenVault.address,
+        userRewardTokenVault: userRewardTokenVault.address,
+        payoutWallet: null,
+        poolStakeTokenVault: pool_config.account.poolStakeTokenVault,
+        poolRewardTokenVault: pool_config.account.poolRewardTokenVault,
+        creatorStakeTokenVault: creatorStakeTokenVault.address,
+        treasuryStakeTokenVault: treasuryStakeTokenVault.address,
+        escrow: null,
+        escrowTotals: null,
+        wsolAccount: null,
+        nativeMint: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([admin.payer, user])
+      .rpc();
+  }
+
 });